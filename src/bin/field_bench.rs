@@ -0,0 +1,175 @@
+use plotters::prelude::*;
+use safe_dsa::define_modulus;
+use safe_dsa::number_theory::field::{Field, Fp};
+use safe_dsa::number_theory::mod_int::{ModInt, Modulus};
+use safe_dsa::number_theory::montgomery::MontgomeryFp;
+use std::error::Error;
+use std::fs;
+use std::hint::black_box;
+use std::path::Path;
+use std::time::Instant;
+
+type FieldFn = fn(&[u64]) -> f64;
+
+struct FieldSpec {
+    name: &'static str,
+    func: FieldFn,
+}
+
+const BASE_SIZES: &[usize] = &[
+    1024, 2048, 4096, 8192, 16_384, 32_768, 65_536, 131_072, 262_144, 524_288, 1_048_576,
+];
+const REPEATS: usize = 5;
+
+const P30: u64 = 998_244_353;
+const P61: u64 = (1 << 61) - 1;
+
+define_modulus!(Runtime30: prime);
+define_modulus!(Runtime61: prime);
+
+fn main() -> Result<(), Box<dyn Error>> {
+    Runtime30::set(P30)?;
+    Runtime61::set(P61)?;
+
+    let specs = [
+        FieldSpec {
+            name: "Fp (30-bit)",
+            func: |raw| time_products(raw, Fp::<P30>::new),
+        },
+        FieldSpec {
+            name: "MontgomeryFp (30-bit)",
+            func: |raw| time_products(raw, MontgomeryFp::<P30>::new),
+        },
+        FieldSpec {
+            name: "ModInt (30-bit)",
+            func: |raw| time_products(raw, ModInt::<Runtime30>::new),
+        },
+        FieldSpec {
+            name: "Fp (61-bit)",
+            func: |raw| time_products(raw, Fp::<P61>::new),
+        },
+        FieldSpec {
+            name: "MontgomeryFp (61-bit)",
+            func: |raw| time_products(raw, MontgomeryFp::<P61>::new),
+        },
+        FieldSpec {
+            name: "ModInt (61-bit)",
+            func: |raw| time_products(raw, ModInt::<Runtime61>::new),
+        },
+    ];
+
+    let series: Vec<_> = specs
+        .iter()
+        .enumerate()
+        .map(|(idx, spec)| {
+            (
+                spec.name,
+                idx,
+                benchmark_series(spec, 2_000 + idx as u64 * 97),
+            )
+        })
+        .collect();
+
+    let max_size = series
+        .iter()
+        .flat_map(|(_, _, data)| data.iter().map(|(n, _)| *n))
+        .max()
+        .unwrap_or(0);
+    let max_time = series
+        .iter()
+        .flat_map(|(_, _, data)| data.iter().map(|(_, t)| *t))
+        .fold(0.0_f64, f64::max)
+        .max(1.0);
+
+    let output_path = Path::new("benchmarks/field_bench.png");
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let root = BitMapBackend::new(output_path, (1280, 720)).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption("Modular Multiplication Performance", ("sans-serif", 36))
+        .margin(20)
+        .x_label_area_size(50)
+        .y_label_area_size(70)
+        .build_cartesian_2d(0usize..max_size, 0f64..max_time)?;
+
+    chart
+        .configure_mesh()
+        .x_desc("Multiply-Adds")
+        .y_desc("Average Time (ms)")
+        .label_style(("sans-serif", 20))
+        .x_labels(10)
+        .y_labels(10)
+        .light_line_style(WHITE.mix(0.15))
+        .draw()?;
+
+    for (name, palette_idx, data) in &series {
+        let idx = *palette_idx;
+        chart
+            .draw_series(LineSeries::new(data.iter().copied(), Palette99::pick(idx)))?
+            .label(*name)
+            .legend(move |(x, y)| {
+                PathElement::new(vec![(x, y), (x + 20, y)], Palette99::pick(idx))
+            });
+        chart.draw_series(
+            data.iter()
+                .map(|(n, t)| Circle::new((*n, *t), 4, Palette99::pick(idx).filled())),
+        )?;
+    }
+
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .label_font(("sans-serif", 22))
+        .draw()?;
+
+    root.present()?;
+
+    println!(
+        "Benchmark complete. Plot saved to {}",
+        output_path.display()
+    );
+
+    Ok(())
+}
+
+/// Converts `raw` into field elements, then times a chain of dependent multiply-adds over them,
+/// in milliseconds. The conversions happen outside the timed section.
+fn time_products<F: Field>(raw: &[u64], new: fn(u64) -> F) -> f64 {
+    let values: Vec<F> = raw.iter().map(|&x| new(x)).collect();
+    let start = Instant::now();
+    let acc = values.iter().fold(F::one(), |acc, &x| acc * x + x);
+    black_box(acc);
+    start.elapsed().as_secs_f64() * 1_000.0
+}
+
+fn benchmark_series(spec: &FieldSpec, seed: u64) -> Vec<(usize, f64)> {
+    BASE_SIZES
+        .iter()
+        .map(|&n| {
+            let mut total = 0.0_f64;
+            for run in 0..REPEATS {
+                let raw = pseudo_random_vec(n, seed + run as u64 * 1_313);
+                total += (spec.func)(&raw);
+            }
+            let avg = total / REPEATS as f64;
+            (n, avg)
+        })
+        .collect()
+}
+
+fn pseudo_random_vec(len: usize, mut seed: u64) -> Vec<u64> {
+    let mut out = Vec::with_capacity(len);
+    for _ in 0..len {
+        seed ^= seed >> 12;
+        seed ^= seed << 25;
+        seed ^= seed >> 27;
+        seed = seed.wrapping_mul(2_685_821_657_736_338_717);
+        out.push(seed);
+    }
+    out
+}
@@ -1,14 +1,18 @@
 use plotters::prelude::*;
+use safe_dsa::sorting::heap_sort::SiftMode;
 use safe_dsa::sorting::{
-    bubble_sort, heap_sort, insertion_sort, merge_sort, quick_sort, selection_sort,
-    shell_sort_a003462,
+    bubble_sort, heap_sort, insertion_sort, merge_sort, natural_merge_sort, quick_sort,
+    selection_sort, shell_sort_a003462, sort_auto, three_way_quick_sort,
 };
+use std::cmp::Ordering;
 use std::error::Error;
 use std::fs;
 use std::path::Path;
+use std::sync::atomic::{self, AtomicU64};
 use std::time::Instant;
 
 type SortFn = fn(&mut [i32]);
+type CountedSortFn = fn(&mut [Counted]);
 
 struct SortSpec {
     name: &'static str,
@@ -16,6 +20,33 @@ struct SortSpec {
     max_size: usize,
 }
 
+struct ComparisonSpec {
+    name: &'static str,
+    func: CountedSortFn,
+}
+
+type Series = Vec<(&'static str, usize, Vec<(usize, f64)>)>;
+
+static COMPARISONS: AtomicU64 = AtomicU64::new(0);
+
+/// An `i32` that counts how often it is compared. Run the benchmark in release mode: contract
+/// checks compare elements too.
+#[derive(Clone, PartialEq, Eq)]
+struct Counted(i32);
+
+impl PartialOrd for Counted {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Counted {
+    fn cmp(&self, other: &Self) -> Ordering {
+        COMPARISONS.fetch_add(1, atomic::Ordering::Relaxed);
+        self.0.cmp(&other.0)
+    }
+}
+
 const BASE_SIZES: &[usize] = &[
     16, 32, 64, 128, 256, 512, 1024, 2048, 4096, 8192, 16_384, 32_768, 65_536, 131_072, 262_144,
     524_288,
@@ -54,11 +85,38 @@ fn main() -> Result<(), Box<dyn Error>> {
             func: heap_sort::sort::<i32>,
             max_size: 1_000_000,
         },
+        SortSpec {
+            name: "Heap Sort (bottom-up)",
+            func: |arr: &mut [i32]| heap_sort::sort_with::<2, i32>(arr, SiftMode::BottomUp),
+            max_size: 1_000_000,
+        },
+        SortSpec {
+            name: "Heap Sort (4-ary, bottom-up)",
+            func: |arr: &mut [i32]| heap_sort::sort_with::<4, i32>(arr, SiftMode::BottomUp),
+            max_size: 1_000_000,
+        },
         SortSpec {
             name: "Shell Sort A003462",
             func: shell_sort_a003462::sort::<i32>,
             max_size: 1_000_000,
         },
+        SortSpec {
+            name: "Natural Merge Sort",
+            func: natural_merge_sort::sort::<i32>,
+            max_size: 1_000_000,
+        },
+        SortSpec {
+            name: "Three-Way Quick Sort",
+            func: three_way_quick_sort::sort::<i32>,
+            max_size: 1_000_000,
+        },
+        SortSpec {
+            name: "Adaptive Sort",
+            func: |arr: &mut [i32]| {
+                sort_auto(arr);
+            },
+            max_size: 1_000_000,
+        },
         SortSpec {
             name: "Rust Unstable Sort",
             func: |arr: &mut [i32]| arr.sort_unstable(),
@@ -66,7 +124,30 @@ fn main() -> Result<(), Box<dyn Error>> {
         },
     ];
 
-    let series: Vec<_> = specs
+    let heap_specs = [
+        ComparisonSpec {
+            name: "Binary, top-down",
+            func: |arr: &mut [Counted]| heap_sort::sort_with::<2, _>(arr, SiftMode::TopDown),
+        },
+        ComparisonSpec {
+            name: "Binary, bottom-up",
+            func: |arr: &mut [Counted]| heap_sort::sort_with::<2, _>(arr, SiftMode::BottomUp),
+        },
+        ComparisonSpec {
+            name: "4-ary, top-down",
+            func: |arr: &mut [Counted]| heap_sort::sort_with::<4, _>(arr, SiftMode::TopDown),
+        },
+        ComparisonSpec {
+            name: "4-ary, bottom-up",
+            func: |arr: &mut [Counted]| heap_sort::sort_with::<4, _>(arr, SiftMode::BottomUp),
+        },
+        ComparisonSpec {
+            name: "8-ary, bottom-up",
+            func: |arr: &mut [Counted]| heap_sort::sort_with::<8, _>(arr, SiftMode::BottomUp),
+        },
+    ];
+
+    let times: Series = specs
         .iter()
         .enumerate()
         .map(|(idx, spec)| {
@@ -77,44 +158,77 @@ fn main() -> Result<(), Box<dyn Error>> {
             )
         })
         .collect();
+    let comparisons: Series = heap_specs
+        .iter()
+        .enumerate()
+        .map(|(idx, spec)| (spec.name, idx, comparison_series(spec, 7_000)))
+        .collect();
 
+    let output_path = Path::new("benchmarks/sort_bench.png");
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let root = BitMapBackend::new(output_path, (1280, 1440)).into_drawing_area();
+    root.fill(&WHITE)?;
+    let (upper, lower) = root.split_vertically(720);
+
+    draw_chart(&upper, "Sorting Performance", "Average Time (ms)", &times)?;
+    draw_chart(
+        &lower,
+        "Heap Sort Comparisons",
+        "Comparisons / (n log2 n)",
+        &comparisons,
+    )?;
+
+    root.present()?;
+
+    println!(
+        "Benchmark complete. Plot saved to {}",
+        output_path.display()
+    );
+
+    Ok(())
+}
+
+fn draw_chart<DB: DrawingBackend>(
+    area: &DrawingArea<DB, plotters::coord::Shift>,
+    caption: &str,
+    y_desc: &str,
+    series: &Series,
+) -> Result<(), Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
     let max_size = series
         .iter()
         .flat_map(|(_, _, data)| data.iter().map(|(n, _)| *n))
         .max()
         .unwrap_or(0);
-    let max_time = series
+    let max_y = series
         .iter()
-        .flat_map(|(_, _, data)| data.iter().map(|(_, t)| *t))
+        .flat_map(|(_, _, data)| data.iter().map(|(_, y)| *y))
         .fold(0.0_f64, f64::max)
         .max(1.0);
 
-    let output_path = Path::new("benchmarks/sort_bench.png");
-    if let Some(parent) = output_path.parent() {
-        fs::create_dir_all(parent)?;
-    }
-
-    let root = BitMapBackend::new(output_path, (1280, 720)).into_drawing_area();
-    root.fill(&WHITE)?;
-
-    let mut chart = ChartBuilder::on(&root)
-        .caption("Sorting Performance", ("sans-serif", 36))
+    let mut chart = ChartBuilder::on(area)
+        .caption(caption, ("sans-serif", 36))
         .margin(20)
         .x_label_area_size(50)
         .y_label_area_size(70)
-        .build_cartesian_2d(0usize..max_size, 0f64..max_time)?;
+        .build_cartesian_2d(0usize..max_size, 0f64..max_y)?;
 
     chart
         .configure_mesh()
         .x_desc("Input Size (elements)")
-        .y_desc("Average Time (ms)")
+        .y_desc(y_desc)
         .label_style(("sans-serif", 20))
         .x_labels(10)
         .y_labels(10)
-        .light_line_style(&WHITE.mix(0.15))
+        .light_line_style(WHITE.mix(0.15))
         .draw()?;
 
-    for (name, palette_idx, data) in &series {
+    for (name, palette_idx, data) in series {
         let idx = *palette_idx;
         chart
             .draw_series(LineSeries::new(data.iter().copied(), Palette99::pick(idx)))?
@@ -124,24 +238,17 @@ fn main() -> Result<(), Box<dyn Error>> {
             });
         chart.draw_series(
             data.iter()
-                .map(|(n, t)| Circle::new((*n, *t), 4, Palette99::pick(idx).filled())),
+                .map(|(n, y)| Circle::new((*n, *y), 4, Palette99::pick(idx).filled())),
         )?;
     }
 
     chart
         .configure_series_labels()
-        .background_style(&WHITE.mix(0.8))
-        .border_style(&BLACK)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
         .label_font(("sans-serif", 22))
         .draw()?;
 
-    root.present()?;
-
-    println!(
-        "Benchmark complete. Plot saved to {}",
-        output_path.display()
-    );
-
     Ok(())
 }
 
@@ -168,6 +275,24 @@ fn benchmark_series(spec: &SortSpec, seed: u64) -> Vec<(usize, f64)> {
         .collect()
 }
 
+/// Comparisons per `n log2 n` of one sort of each size: the count is deterministic for a
+/// given input, so there is nothing to average.
+fn comparison_series(spec: &ComparisonSpec, seed: u64) -> Vec<(usize, f64)> {
+    BASE_SIZES
+        .iter()
+        .map(|&n| {
+            let mut data: Vec<Counted> = pseudo_random_vec(n, seed)
+                .into_iter()
+                .map(Counted)
+                .collect();
+            COMPARISONS.store(0, atomic::Ordering::Relaxed);
+            (spec.func)(&mut data);
+            let count = COMPARISONS.load(atomic::Ordering::Relaxed) as f64;
+            (n, count / (n as f64 * (n as f64).log2()))
+        })
+        .collect()
+}
+
 fn pseudo_random_vec(len: usize, mut seed: u64) -> Vec<i32> {
     let mut out = Vec::with_capacity(len);
     for _ in 0..len {
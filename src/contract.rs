@@ -0,0 +1,300 @@
+/*!
+Contracts
+
+Every precondition, postcondition, struct invariant and loop invariant of this crate is
+checked whenever [ENABLED] is: by default in debug builds only, and with the `checked` cargo
+feature enabled in every build.
+- Contracts are the `requires`, `ensures` and `invariant` attributes of the `contracts` crate,
+  all of an item's under one `cfg_attr`, so that they are compiled out together:
+  ```ignore
+  #[cfg_attr(
+      any(debug_assertions, feature = "checked"),
+      contracts::requires(x > 0),
+      contracts::ensures(ret > x)
+  )]
+  fn next(x: i32) -> i32 { x + 1 }
+  ```
+  An `invariant` on an impl block adds itself to each method by its bare name, so files
+  using one must also `use contracts::invariant` under the same `cfg`.
+- `loop_invariant!` asserts a condition inside a function body.
+
+A violated contract panics. [catch] turns such a panic into a structured [Violation]
+reporting the kind of contract, the module, the function and the violated expression.
+*/
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::error::Error;
+use std::fmt;
+use std::panic::{self, UnwindSafe};
+use std::sync::Once;
+
+/// Whether contracts are checked in this build
+pub const ENABLED: bool = cfg!(any(debug_assertions, feature = "checked"));
+
+/// Asserts a loop invariant (or any other in-body assertion) of the enclosing function.
+///
+/// Checked whenever [ENABLED] is; the panic message is parsed back by [catch].
+macro_rules! loop_invariant {
+    ($cond:expr $(,)?) => {
+        if $crate::contract::ENABLED && !$cond {
+            $crate::contract::violated(
+                {
+                    fn f() {}
+                    ::std::any::type_name_of_val(&f)
+                },
+                stringify!($cond),
+            );
+        }
+    };
+}
+pub(crate) use loop_invariant;
+
+/// [Clone] whenever [ENABLED], so that a postcondition can copy its input with `old(..)`, and
+/// implemented by every type otherwise. Functions that only need to copy their input for a
+/// contract take `T: Snapshot` instead of `T: Clone`, and so keep their release bounds.
+#[cfg(any(debug_assertions, feature = "checked"))]
+pub trait Snapshot: Clone {}
+#[cfg(any(debug_assertions, feature = "checked"))]
+impl<T: Clone> Snapshot for T {}
+
+/// Implemented by every type: contracts are compiled out of this build
+#[cfg(not(any(debug_assertions, feature = "checked")))]
+pub trait Snapshot {}
+#[cfg(not(any(debug_assertions, feature = "checked")))]
+impl<T> Snapshot for T {}
+
+/// The kind of a violated contract
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Precondition,
+    Postcondition,
+    Invariant,
+    LoopInvariant,
+}
+
+impl Kind {
+    /// The name used in panic messages, as produced by the `contracts` crate
+    fn message_name(self) -> &'static str {
+        match self {
+            Kind::Precondition => "Pre-condition",
+            Kind::Postcondition => "Post-condition",
+            Kind::Invariant => "Invariant",
+            Kind::LoopInvariant => "Loop invariant",
+        }
+    }
+}
+
+/// A violated contract: which one, and where.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    pub kind: Kind,
+    /// Module path, e.g. `safe_dsa::sorting::heap_sort` (empty if unknown)
+    pub module: String,
+    /// Name of the function the contract belongs to
+    pub function: String,
+    /// Source text of the violated condition
+    pub expression: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} of ", self.kind.message_name())?;
+        if !self.module.is_empty() {
+            write!(f, "{}::", self.module)?;
+        }
+        write!(f, "{} violated: {}", self.function, self.expression)
+    }
+}
+
+impl Error for Violation {}
+
+impl Violation {
+    /// Parses a panic message of the form `"<kind> of <function> violated: <expression>"`.
+    /// Returns `None` for panics that are not contract violations.
+    pub fn from_message(message: &str) -> Option<Violation> {
+        let (head, expression) = message.split_once(" violated: ")?;
+        let (kind, function) = head.split_once(" of ")?;
+        let kind = match kind {
+            "Pre-condition" => Kind::Precondition,
+            "Post-condition" => Kind::Postcondition,
+            "Invariant (as pre-condition)" | "Invariant (as post-condition)" => Kind::Invariant,
+            "Loop invariant" => Kind::LoopInvariant,
+            _ => return None,
+        };
+        Some(Violation {
+            kind,
+            module: String::new(),
+            function: function.to_string(),
+            expression: expression.to_string(),
+        })
+    }
+}
+
+/// Panics with the message of a violated `loop_invariant!`.
+/// `path` is the type name of an item nested in the violating function.
+#[cold]
+#[track_caller]
+pub fn violated(path: &str, expression: &str) -> ! {
+    // "crate::module::function::{{closure}}::f" -> "function"
+    let function = path
+        .rsplit("::")
+        .find(|s| *s != "f" && !s.starts_with('{'))
+        .unwrap_or(path);
+    panic!(
+        "{} of {} violated: {}",
+        Kind::LoopInvariant.message_name(),
+        function,
+        expression
+    );
+}
+
+thread_local! {
+    static LAST_PANIC_FILE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+/// Records the source file of every panic, then defers to the previous hook.
+fn install_hook() {
+    INSTALL_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let file = info.location().map(|l| l.file().to_string());
+            LAST_PANIC_FILE.with(|f| *f.borrow_mut() = file);
+            previous(info);
+        }));
+    });
+}
+
+/// `.../src/sorting/heap_sort.rs` -> `safe_dsa::sorting::heap_sort`
+fn module_of_file(file: &str) -> String {
+    let file = file.replace('\\', "/");
+    let relative = match file.rfind("src/") {
+        Some(i) => &file[i + 4..],
+        None => return String::new(),
+    };
+    let relative = relative.trim_end_matches(".rs");
+    let relative = match relative {
+        "lib" => "",
+        _ => relative.strip_suffix("/mod").unwrap_or(relative),
+    };
+    let mut module = String::from(env!("CARGO_CRATE_NAME"));
+    for segment in relative.split('/').filter(|s| !s.is_empty()) {
+        module.push_str("::");
+        module.push_str(segment);
+    }
+    module
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> Option<&str> {
+    if let Some(s) = payload.downcast_ref::<String>() {
+        Some(s)
+    } else {
+        payload.downcast_ref::<&str>().copied()
+    }
+}
+
+/// Runs `f`, converting a contract violation inside it into a [Violation].
+/// Panics that are not contract violations are propagated unchanged.
+pub fn catch<R, F: FnOnce() -> R + UnwindSafe>(f: F) -> Result<R, Violation> {
+    install_hook();
+    match panic::catch_unwind(f) {
+        Ok(r) => Ok(r),
+        Err(payload) => match panic_message(payload.as_ref()).and_then(Violation::from_message) {
+            Some(mut violation) => {
+                if let Some(file) = LAST_PANIC_FILE.with(|f| f.borrow_mut().take()) {
+                    violation.module = module_of_file(&file);
+                }
+                Err(violation)
+            }
+            None => panic::resume_unwind(payload),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fails_loop_invariant(n: usize) {
+        for i in 0..n {
+            loop_invariant!(i < 2);
+        }
+    }
+
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(x > 0)
+    )]
+    fn positive(x: i32) -> i32 {
+        x
+    }
+
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(ret >= 0),
+        contracts::ensures(ret % 2 == 0)
+    )]
+    fn even(x: i32) -> i32 {
+        x
+    }
+
+    #[test]
+    fn stacked_contracts_follow_enabled() {
+        assert_eq!(catch(|| even(2)), Ok(2));
+        let result = catch(|| even(3));
+        assert_eq!(result.is_err(), ENABLED);
+        if let Err(v) = result {
+            assert_eq!(v.kind, Kind::Postcondition);
+            assert_eq!(v.expression, "ret % 2 == 0");
+        }
+    }
+
+    #[test]
+    fn parses_contracts_messages() {
+        let v = Violation::from_message("Post-condition of sort violated: is_sorted(arr)").unwrap();
+        assert_eq!(v.kind, Kind::Postcondition);
+        assert_eq!(v.function, "sort");
+        assert_eq!(v.expression, "is_sorted(arr)");
+        assert!(Violation::from_message("index out of bounds").is_none());
+    }
+
+    #[test]
+    fn module_from_file() {
+        assert_eq!(
+            module_of_file("src/sorting/heap_sort.rs"),
+            "safe_dsa::sorting::heap_sort"
+        );
+        assert_eq!(
+            module_of_file("/x/src/number_theory/mod.rs"),
+            "safe_dsa::number_theory"
+        );
+    }
+
+    #[test]
+    fn catches_loop_invariant() {
+        assert_eq!(catch(|| fails_loop_invariant(2)), Ok(()));
+        if !ENABLED {
+            return;
+        }
+        let v = catch(|| fails_loop_invariant(5)).unwrap_err();
+        assert_eq!(v.kind, Kind::LoopInvariant);
+        assert_eq!(v.module, "safe_dsa::contract");
+        assert_eq!(v.function, "fails_loop_invariant");
+        assert_eq!(v.expression, "i < 2");
+    }
+
+    #[test]
+    fn catches_precondition() {
+        assert_eq!(catch(|| positive(1)), Ok(1));
+        if !ENABLED {
+            return;
+        }
+        let v = catch(|| positive(0)).unwrap_err();
+        assert_eq!(v.kind, Kind::Precondition);
+        assert_eq!(v.function, "positive");
+        assert_eq!(v.expression, "x > 0");
+        assert_eq!(v.module, "safe_dsa::contract");
+    }
+}
@@ -0,0 +1,316 @@
+/*!
+Data Structures
+
+Binary Heap
+
+Implements:
+- [BinaryHeap], with the aliases [MaxHeap] and [MinHeap]
+
+A priority queue stored as an implicit binary tree in a [Vec], sharing its sift-down with
+[heap_sort](crate::sorting::heap_sort). The heap property is a struct invariant, checked
+before and after every method call in debug mode, which makes each call `O(n)` there.
+*/
+
+use crate::data_structures::priority_queue::{Max, Min, Order, PriorityQueue};
+use crate::sorting::heap_sort::{sift_down_by, sift_up_by};
+#[cfg(any(debug_assertions, feature = "checked"))]
+use crate::sorting::helper::is_heap_by;
+#[cfg(any(debug_assertions, feature = "checked"))]
+use contracts::invariant;
+use std::fmt;
+use std::marker::PhantomData;
+
+/**
+Binary Heap
+
+A priority queue whose top element, the greatest for [Max] and the least for [Min],
+can be read in `O(1)`, and which supports insertion and removal of the top in `O(log n)`.
+*/
+pub struct BinaryHeap<T, O = Max> {
+    data: Vec<T>,
+    order: PhantomData<O>,
+}
+
+/// A [BinaryHeap] with the greatest element on top
+pub type MaxHeap<T> = BinaryHeap<T, Max>;
+
+/// A [BinaryHeap] with the least element on top
+pub type MinHeap<T> = BinaryHeap<T, Min>;
+
+impl<T: Ord, O: Order> BinaryHeap<T, O> {
+    /// Creates an empty heap
+    pub fn new() -> Self {
+        BinaryHeap {
+            data: Vec::new(),
+            order: PhantomData,
+        }
+    }
+
+    /// Creates an empty heap with space for at least `capacity` elements
+    pub fn with_capacity(capacity: usize) -> Self {
+        BinaryHeap {
+            data: Vec::with_capacity(capacity),
+            order: PhantomData,
+        }
+    }
+}
+
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::invariant(is_heap_by(&self.data, O::below))
+)]
+impl<T: Ord, O: Order> BinaryHeap<T, O> {
+    /// Number of elements in the heap
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Whether the heap is empty
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the top element, or `None` if the heap is empty
+    ///
+    /// Time Complexity: `O(1)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(ret.is_none_or(|top| self.data.iter().all(|x| !O::below(top, x))))
+    )]
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    /// Inserts `x`
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(self.len() == old(self.len()) + 1)
+    )]
+    pub fn push(&mut self, x: T) {
+        self.data.push(x);
+        let last = self.data.len() - 1;
+        sift_up_by::<2, _, _>(&mut self.data, last, O::below);
+    }
+
+    /// Removes and returns the top element, or `None` if the heap is empty
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(self.len() == old(self.len()).saturating_sub(1)),
+        contracts::ensures(ret.as_ref().is_none_or(|top| self.data.iter().all(|x| !O::below(top, x))))
+    )]
+    pub fn pop(&mut self) -> Option<T> {
+        let last = self.data.len().checked_sub(1)?;
+        self.data.swap(0, last);
+        let top = self.data.pop();
+        if last > 0 {
+            sift_down_by::<2, _, _>(&mut self.data, 0, last - 1, O::below);
+        }
+        top
+    }
+
+    /// Removes all elements
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(self.is_empty())
+    )]
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Iterates over the elements in no particular order
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.data.iter()
+    }
+}
+
+impl<T: Ord, O: Order> BinaryHeap<T, O> {
+    /// Returns the elements in no particular order
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    /// Returns the elements sorted so that the top of the heap comes last, like
+    /// [std::collections::BinaryHeap::into_sorted_vec]: ascending for a [MaxHeap],
+    /// descending for a [MinHeap].
+    ///
+    /// Time Complexity: `O(n log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(ret.windows(2).all(|w| !O::below(&w[1], &w[0])))
+    )]
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        let mut data = self.data;
+        for end in (1..data.len()).rev() {
+            data.swap(0, end);
+            sift_down_by::<2, _, _>(&mut data, 0, end - 1, O::below);
+        }
+        data
+    }
+}
+
+impl<T: Ord, O: Order> From<Vec<T>> for BinaryHeap<T, O> {
+    /// Heapifies `data` in place
+    ///
+    /// Time Complexity: `O(n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(is_heap_by(&ret.data, O::below))
+    )]
+    fn from(mut data: Vec<T>) -> Self {
+        let n = data.len();
+        for i in (0..n / 2).rev() {
+            sift_down_by::<2, _, _>(&mut data, i, n - 1, O::below);
+        }
+        BinaryHeap {
+            data,
+            order: PhantomData,
+        }
+    }
+}
+
+impl<T: Ord, O: Order> PriorityQueue<T> for BinaryHeap<T, O> {
+    type Order = O;
+
+    fn len(&self) -> usize {
+        BinaryHeap::len(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        BinaryHeap::peek(self)
+    }
+
+    fn push(&mut self, x: T) {
+        BinaryHeap::push(self, x);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        BinaryHeap::pop(self)
+    }
+}
+
+impl<T: Ord, O: Order> Default for BinaryHeap<T, O> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord, O: Order> FromIterator<T> for BinaryHeap<T, O> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<T>>())
+    }
+}
+
+impl<T: Ord, O: Order> Extend<T> for BinaryHeap<T, O> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+impl<T: Clone, O> Clone for BinaryHeap<T, O> {
+    fn clone(&self) -> Self {
+        BinaryHeap {
+            data: self.data.clone(),
+            order: PhantomData,
+        }
+    }
+}
+
+impl<T: fmt::Debug, O> fmt::Debug for BinaryHeap<T, O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.data).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{ENABLED, Kind, catch};
+    use crate::data_structures::pq_test_helper::test_priority_queue;
+    use crate::sorting::helper::is_sorted;
+    use crate::sorting::prop_test::Rng;
+
+    #[test]
+    fn test_binary_heaps() {
+        test_priority_queue(MaxHeap::<i64>::new);
+        test_priority_queue(MinHeap::<i64>::new);
+    }
+
+    #[test]
+    fn max_heap_pops_in_descending_order() {
+        let mut heap = MaxHeap::new();
+        for x in [5, 1, 8, 3, 9, 2, 8] {
+            heap.push(x);
+        }
+        assert_eq!(heap.len(), 7);
+        assert_eq!(heap.peek(), Some(&9));
+        let mut popped = Vec::new();
+        while let Some(x) = heap.pop() {
+            popped.push(x);
+        }
+        assert_eq!(popped, [9, 8, 8, 5, 3, 2, 1]);
+        assert!(heap.is_empty());
+        assert_eq!(heap.pop(), None);
+    }
+
+    #[test]
+    fn min_heap_from_vec() {
+        let mut heap: MinHeap<i32> = vec![7, -2, 4, 0, 4].into();
+        assert_eq!(heap.peek(), Some(&-2));
+        assert_eq!(heap.pop(), Some(-2));
+        assert_eq!(heap.pop(), Some(0));
+        heap.push(-10);
+        assert_eq!(heap.peek(), Some(&-10));
+    }
+
+    #[test]
+    fn into_sorted_vec_matches_std() {
+        let data: Vec<i64> = (0..200).map(|i| (i * 7919) % 211 - 100).collect();
+        let max: MaxHeap<i64> = data.iter().copied().collect();
+        let sorted = max.into_sorted_vec();
+        assert!(is_sorted(&sorted));
+        assert_eq!(
+            sorted,
+            std::collections::BinaryHeap::from(data.clone()).into_sorted_vec()
+        );
+
+        let min: MinHeap<i64> = data.into_iter().collect();
+        let mut descending = min.into_sorted_vec();
+        descending.reverse();
+        assert_eq!(descending, sorted);
+    }
+
+    #[test]
+    fn interleaved_operations_agree_with_std() {
+        let mut ours = MinHeap::new();
+        let mut std_heap = std::collections::BinaryHeap::new();
+        let mut rng = Rng::new(12345);
+        for _ in 0..1000 {
+            let x = rng.below(100);
+            if x.is_multiple_of(3) {
+                assert_eq!(ours.pop(), std_heap.pop().map(|std::cmp::Reverse(y)| y));
+            } else {
+                ours.push(x);
+                std_heap.push(std::cmp::Reverse(x));
+            }
+            assert_eq!(ours.len(), std_heap.len());
+        }
+    }
+
+    #[test]
+    fn corrupted_heap_violates_invariant() {
+        let mut heap: MinHeap<i32> = vec![3, 1, 2].into();
+        heap.data.push(0);
+        let result = catch(move || heap.len());
+        assert_eq!(result.is_err(), ENABLED);
+        if let Err(v) = result {
+            assert_eq!(v.kind, Kind::Invariant);
+            assert_eq!(v.module, "safe_dsa::data_structures::binary_heap");
+        }
+    }
+}
@@ -0,0 +1,204 @@
+/*!
+Data Structures
+
+D-ary Heap
+
+Implements:
+- [DaryHeap]
+
+A heap in which every node has up to `D` children, stored in a [Vec] with the children of `i`
+at `D * i + 1 ..= D * i + D`. A larger `D` makes the tree shallower, so pushes are cheaper
+and pops compare more children per level. `D = 2` is the [BinaryHeap](super::binary_heap::BinaryHeap).
+*/
+
+use crate::data_structures::priority_queue::{Max, Order, PriorityQueue};
+use crate::sorting::heap_sort::{sift_down_by, sift_up_by};
+#[cfg(any(debug_assertions, feature = "checked"))]
+use crate::sorting::helper::is_dary_heap_by;
+#[cfg(any(debug_assertions, feature = "checked"))]
+use contracts::invariant;
+use std::fmt;
+use std::marker::PhantomData;
+
+/**
+D-ary Heap
+
+A priority queue whose top element, the greatest for [Max] and the least for
+[Min](super::priority_queue::Min), can be read in `O(1)`. `D` must be at least `2`.
+
+Push Time Complexity: `O(log n / log D)`
+Pop Time Complexity: `O(D log n / log D)`
+*/
+pub struct DaryHeap<T, const D: usize, O = Max> {
+    data: Vec<T>,
+    order: PhantomData<O>,
+}
+
+impl<T: Ord, const D: usize, O: Order> DaryHeap<T, D, O> {
+    /// Rejects `D < 2` at compile time
+    const VALID_ARITY: () = assert!(D >= 2, "a d-ary heap needs at least 2 children per node");
+
+    /// Creates an empty heap
+    pub fn new() -> Self {
+        let () = Self::VALID_ARITY;
+        DaryHeap {
+            data: Vec::new(),
+            order: PhantomData,
+        }
+    }
+}
+
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::invariant(is_dary_heap_by(&self.data, D, O::below))
+)]
+impl<T: Ord, const D: usize, O: Order> DaryHeap<T, D, O> {
+    /// Number of elements in the heap
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Whether the heap is empty
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the top element, or `None` if the heap is empty
+    ///
+    /// Time Complexity: `O(1)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(ret.is_none_or(|top| self.data.iter().all(|x| !O::below(top, x))))
+    )]
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    /// Inserts `x`
+    ///
+    /// Time Complexity: `O(log n / log D)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(self.len() == old(self.len()) + 1)
+    )]
+    pub fn push(&mut self, x: T) {
+        self.data.push(x);
+        let last = self.data.len() - 1;
+        sift_up_by::<D, _, _>(&mut self.data, last, O::below);
+    }
+
+    /// Removes and returns the top element, or `None` if the heap is empty
+    ///
+    /// Time Complexity: `O(D log n / log D)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(self.len() == old(self.len()).saturating_sub(1)),
+        contracts::ensures(ret.as_ref().is_none_or(|top| self.data.iter().all(|x| !O::below(top, x))))
+    )]
+    pub fn pop(&mut self) -> Option<T> {
+        let last = self.data.len().checked_sub(1)?;
+        self.data.swap(0, last);
+        let top = self.data.pop();
+        if last > 0 {
+            sift_down_by::<D, _, _>(&mut self.data, 0, last - 1, O::below);
+        }
+        top
+    }
+}
+
+impl<T: Ord, const D: usize, O: Order> DaryHeap<T, D, O> {
+    /// Returns the elements in no particular order
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+}
+
+impl<T: Ord, const D: usize, O: Order> From<Vec<T>> for DaryHeap<T, D, O> {
+    /// Heapifies `data` in place
+    ///
+    /// Time Complexity: `O(n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(is_dary_heap_by(&ret.data, D, O::below))
+    )]
+    fn from(mut data: Vec<T>) -> Self {
+        let () = Self::VALID_ARITY;
+        let n = data.len();
+        // Every index past the parent of the last element is a leaf
+        for i in (0..n.saturating_sub(1).div_ceil(D)).rev() {
+            sift_down_by::<D, _, _>(&mut data, i, n - 1, O::below);
+        }
+        DaryHeap {
+            data,
+            order: PhantomData,
+        }
+    }
+}
+
+impl<T: Ord, const D: usize, O: Order> PriorityQueue<T> for DaryHeap<T, D, O> {
+    type Order = O;
+
+    fn len(&self) -> usize {
+        DaryHeap::len(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        DaryHeap::peek(self)
+    }
+
+    fn push(&mut self, x: T) {
+        DaryHeap::push(self, x);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        DaryHeap::pop(self)
+    }
+}
+
+impl<T: Ord, const D: usize, O: Order> Default for DaryHeap<T, D, O> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, const D: usize, O> Clone for DaryHeap<T, D, O> {
+    fn clone(&self) -> Self {
+        DaryHeap {
+            data: self.data.clone(),
+            order: PhantomData,
+        }
+    }
+}
+
+impl<T: fmt::Debug, const D: usize, O> fmt::Debug for DaryHeap<T, D, O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.data).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structures::pq_test_helper::test_priority_queue;
+    use crate::data_structures::priority_queue::Min;
+
+    #[test]
+    fn test_dary_heaps() {
+        test_priority_queue(DaryHeap::<i64, 2>::new);
+        test_priority_queue(DaryHeap::<i64, 3, Min>::new);
+        test_priority_queue(DaryHeap::<i64, 4>::new);
+        test_priority_queue(DaryHeap::<i64, 8, Min>::new);
+    }
+
+    #[test]
+    fn heapify_from_vec() {
+        for n in 0..40 {
+            let data: Vec<i64> = (0..n).map(|i| (i * 37) % 11).collect();
+            let mut heap = DaryHeap::<i64, 3, Min>::from(data.clone());
+            let mut sorted = data;
+            sorted.sort();
+            let popped: Vec<i64> = std::iter::from_fn(|| heap.pop()).collect();
+            assert_eq!(popped, sorted);
+        }
+    }
+}
@@ -0,0 +1,544 @@
+/*!
+Data Structures
+
+Fenwick Tree (Binary Indexed Tree)
+
+Implements:
+- [AdditiveGroup], for signed integers and every [Field]
+- [FenwickTree], with point updates and prefix sums
+- [FenwickTree2D], with point updates and rectangle sums
+- [RangeFenwickTree], with range updates and range sums
+
+Element `i` of the tree stores the sum of the elements `i & (i + 1) ..= i`, so that both a
+prefix sum and the elements covering an index are found by clearing or setting one bit at a
+time, in `O(log n)` steps.
+*/
+
+use crate::number_theory::field::Field;
+use std::fmt;
+use std::ops::{Add, Neg, Sub};
+
+/// A commutative group under `+`, with identity [zero](AdditiveGroup::zero) and inverse `-`.
+/// Sums are only exact for exact types; those of [f64] are rounded.
+pub trait AdditiveGroup:
+    Copy + PartialEq + Add<Output = Self> + Sub<Output = Self> + Neg<Output = Self>
+{
+    /// Additive identity
+    fn zero() -> Self;
+
+    /// `self` added `n` times
+    #[inline]
+    fn times(self, mut n: usize) -> Self {
+        let mut base = self;
+        let mut acc = Self::zero();
+        while n > 0 {
+            if n & 1 == 1 {
+                acc = acc + base;
+            }
+            n >>= 1;
+            // Doubling past the last bit could overflow even though the result fits
+            if n > 0 {
+                base = base + base;
+            }
+        }
+        acc
+    }
+}
+
+impl<F: Field> AdditiveGroup for F {
+    #[inline]
+    fn zero() -> Self {
+        <F as Field>::zero()
+    }
+}
+
+macro_rules! impl_additive_group {
+    ($($t:ty),*) => {$(
+        impl AdditiveGroup for $t {
+            #[inline]
+            fn zero() -> Self {
+                0
+            }
+        }
+    )*};
+}
+
+impl_additive_group!(i8, i16, i32, i64, i128, isize);
+
+/// The sum of the first `k` elements of the Fenwick tree `tree`
+fn prefix<G: AdditiveGroup>(tree: &[G], mut k: usize) -> G {
+    let mut sum = G::zero();
+    while k > 0 {
+        sum = sum + tree[k - 1];
+        k &= k - 1;
+    }
+    sum
+}
+
+/// Adds `delta` to element `i` of the Fenwick tree `tree`
+fn update<G: AdditiveGroup>(tree: &mut [G], mut i: usize, delta: G) {
+    while i < tree.len() {
+        tree[i] = tree[i] + delta;
+        i |= i + 1;
+    }
+}
+
+/**
+Fenwick Tree
+
+An array of `n` group elements supporting point updates and prefix sums, and with them range
+sums, in `O(log n)`, such as running totals modulo a prime with [Fp](crate::number_theory::field::Fp).
+
+Update Time Complexity: `O(log n)`
+Query Time Complexity: `O(log n)`
+Space Complexity: `O(n)`
+*/
+#[derive(Clone)]
+pub struct FenwickTree<G> {
+    tree: Vec<G>,
+}
+
+impl<G: AdditiveGroup> FenwickTree<G> {
+    /// Creates a tree of `n` zeros
+    pub fn new(n: usize) -> Self {
+        FenwickTree {
+            tree: vec![G::zero(); n],
+        }
+    }
+
+    /// Number of elements
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Whether there are no elements
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Adds `delta` to element `i`
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(i < self.len())
+    )]
+    pub fn add(&mut self, i: usize, delta: G) {
+        update(&mut self.tree, i, delta);
+    }
+
+    /// Sets element `i` to `x`
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(i < self.len())
+    )]
+    pub fn set(&mut self, i: usize, x: G) {
+        let delta = x - self.get(i);
+        self.add(i, delta);
+    }
+
+    /// Returns element `i`
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(i < self.len())
+    )]
+    pub fn get(&self, i: usize) -> G {
+        self.range_sum(i, i + 1)
+    }
+
+    /// The sum of the first `k` elements
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(k <= self.len())
+    )]
+    pub fn prefix_sum(&self, k: usize) -> G {
+        prefix(&self.tree, k)
+    }
+
+    /// The sum of the elements `l..r`
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(l <= r && r <= self.len())
+    )]
+    pub fn range_sum(&self, l: usize, r: usize) -> G {
+        self.prefix_sum(r) - self.prefix_sum(l)
+    }
+}
+
+impl<G: AdditiveGroup + PartialOrd> FenwickTree<G> {
+    /// The least `k` such that the sum of the first `k` elements is at least `target`, or
+    /// `None` if the total is less. The elements must not be negative, so that prefix sums
+    /// are nondecreasing.
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(ret.is_none_or(|k| k <= self.len()))
+    )]
+    pub fn lower_bound(&self, target: G) -> Option<usize> {
+        if target <= G::zero() {
+            return Some(0);
+        }
+        let n = self.len();
+        // Extend the longest prefix whose sum stays below target, one power of two at a time
+        let mut taken = 0;
+        let mut sum = G::zero();
+        let mut step = if n == 0 { 0 } else { 1 << n.ilog2() };
+        while step > 0 {
+            if taken + step <= n && sum + self.tree[taken + step - 1] < target {
+                taken += step;
+                sum = sum + self.tree[taken - 1];
+            }
+            step >>= 1;
+        }
+        (taken < n).then_some(taken + 1)
+    }
+}
+
+impl<G: AdditiveGroup> From<Vec<G>> for FenwickTree<G> {
+    /// Builds the tree over the given elements in place
+    ///
+    /// Time Complexity: `O(n)`
+    fn from(mut tree: Vec<G>) -> Self {
+        let n = tree.len();
+        // Each element passes its range sum on to the next element covering it
+        for i in 0..n {
+            let j = i | (i + 1);
+            if j < n {
+                tree[j] = tree[j] + tree[i];
+            }
+        }
+        FenwickTree { tree }
+    }
+}
+
+impl<G: AdditiveGroup + fmt::Debug> fmt::Debug for FenwickTree<G> {
+    /// Shows the elements, not the partial sums stored
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries((0..self.len()).map(|i| self.get(i)))
+            .finish()
+    }
+}
+
+/**
+2D Fenwick Tree
+
+A `rows × cols` grid of group elements supporting point updates and sums over rectangles.
+
+Update Time Complexity: `O(log rows log cols)`
+Query Time Complexity: `O(log rows log cols)`
+Space Complexity: `O(rows cols)`
+*/
+#[derive(Clone)]
+pub struct FenwickTree2D<G> {
+    rows: usize,
+    cols: usize,
+    /// Row-major: row `r` is a Fenwick tree over the column sums of a range of rows
+    tree: Vec<G>,
+}
+
+impl<G: AdditiveGroup> FenwickTree2D<G> {
+    /// Creates a `rows × cols` grid of zeros
+    pub fn new(rows: usize, cols: usize) -> Self {
+        FenwickTree2D {
+            rows,
+            cols,
+            tree: vec![G::zero(); rows.checked_mul(cols).expect("grid too large")],
+        }
+    }
+
+    /// Number of rows
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Number of columns
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Adds `delta` to the element at row `r` and column `c`
+    ///
+    /// Time Complexity: `O(log rows log cols)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(r < self.rows && c < self.cols)
+    )]
+    pub fn add(&mut self, mut r: usize, c: usize, delta: G) {
+        while r < self.rows {
+            update(&mut self.tree[r * self.cols..(r + 1) * self.cols], c, delta);
+            r |= r + 1;
+        }
+    }
+
+    /// The sum of the elements in the first `r` rows and first `c` columns
+    ///
+    /// Time Complexity: `O(log rows log cols)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(r <= self.rows && c <= self.cols)
+    )]
+    pub fn prefix_sum(&self, mut r: usize, c: usize) -> G {
+        let mut sum = G::zero();
+        while r > 0 {
+            sum = sum + prefix(&self.tree[(r - 1) * self.cols..r * self.cols], c);
+            r &= r - 1;
+        }
+        sum
+    }
+
+    /// The sum of the elements in rows `r1..r2` and columns `c1..c2`
+    ///
+    /// Time Complexity: `O(log rows log cols)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(r1 <= r2 && r2 <= self.rows),
+        contracts::requires(c1 <= c2 && c2 <= self.cols)
+    )]
+    pub fn rect_sum(&self, r1: usize, c1: usize, r2: usize, c2: usize) -> G {
+        self.prefix_sum(r2, c2) - self.prefix_sum(r1, c2) - self.prefix_sum(r2, c1)
+            + self.prefix_sum(r1, c1)
+    }
+}
+
+/**
+Range-Update Fenwick Tree
+
+An array of `n` group elements supporting adding a value to a range of elements, and range
+sums, both in `O(log n)`.
+
+Two Fenwick trees `a` and `b` store the differences of the array, so that the sum of the
+first `k` elements is `k · prefix(a, k) - prefix(b, k)`, where an update of `l..r` by `x`
+adds `x` at `l` and `-x` at `r` in `a`, and `l · x` and `-r · x` in `b`.
+
+Update Time Complexity: `O(log n)`
+Query Time Complexity: `O(log n)`
+Space Complexity: `O(n)`
+*/
+#[derive(Clone)]
+pub struct RangeFenwickTree<G> {
+    a: Vec<G>,
+    b: Vec<G>,
+}
+
+impl<G: AdditiveGroup> RangeFenwickTree<G> {
+    /// Creates a tree of `n` zeros
+    pub fn new(n: usize) -> Self {
+        RangeFenwickTree {
+            a: vec![G::zero(); n],
+            b: vec![G::zero(); n],
+        }
+    }
+
+    /// Number of elements
+    pub fn len(&self) -> usize {
+        self.a.len()
+    }
+
+    /// Whether there are no elements
+    pub fn is_empty(&self) -> bool {
+        self.a.is_empty()
+    }
+
+    /// Adds `delta` to `x` at `i` of the difference arrays, which adds it to every element
+    /// from `i` on
+    fn add_suffix(&mut self, i: usize, delta: G) {
+        if i < self.len() {
+            update(&mut self.a, i, delta);
+            update(&mut self.b, i, delta.times(i));
+        }
+    }
+
+    /// Adds `delta` to every element of `l..r`
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(l <= r && r <= self.len())
+    )]
+    pub fn range_add(&mut self, l: usize, r: usize, delta: G) {
+        self.add_suffix(l, delta);
+        self.add_suffix(r, -delta);
+    }
+
+    /// The sum of the first `k` elements
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(k <= self.len())
+    )]
+    pub fn prefix_sum(&self, k: usize) -> G {
+        prefix(&self.a, k).times(k) - prefix(&self.b, k)
+    }
+
+    /// The sum of the elements `l..r`
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(l <= r && r <= self.len())
+    )]
+    pub fn range_sum(&self, l: usize, r: usize) -> G {
+        self.prefix_sum(r) - self.prefix_sum(l)
+    }
+
+    /// Returns element `i`
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(i < self.len())
+    )]
+    pub fn get(&self, i: usize) -> G {
+        prefix(&self.a, i + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number_theory::field::Fp;
+    use crate::number_theory::rational::Rational;
+    use crate::sorting::prop_test::Rng;
+
+    type F = Fp<1_000_000_007>;
+
+    #[test]
+    fn point_updates_agree_with_model() {
+        let n = 50;
+        let mut rng = Rng::new(39);
+        let mut model: Vec<i64> = (0..n).map(|_| rng.symmetric(100) as i64).collect();
+        let mut tree = FenwickTree::from(model.clone());
+        for _ in 0..500 {
+            let i = rng.below(n as u64) as usize;
+            let x = rng.symmetric(100) as i64;
+            if rng.below(2) == 0 {
+                tree.add(i, x);
+                model[i] += x;
+            } else {
+                tree.set(i, x);
+                model[i] = x;
+            }
+            let l = rng.below(n as u64 + 1) as usize;
+            let r = l + rng.below((n - l) as u64 + 1) as usize;
+            assert_eq!(tree.range_sum(l, r), model[l..r].iter().sum::<i64>());
+            assert_eq!(tree.get(i), model[i]);
+        }
+        assert_eq!(format!("{tree:?}"), format!("{model:?}"));
+    }
+
+    #[test]
+    fn prefix_sums_modulo_prime() {
+        let values: Vec<F> = (1..=20).map(|i| F::new(i * 100_000_000)).collect();
+        let mut tree = FenwickTree::from(values);
+        // 100_000_000 * (1 + ... + 20) = 21_000_000_000 = 20 * (10^9 + 7) + 999_999_860
+        assert_eq!(tree.prefix_sum(20).value(), 999_999_860);
+        tree.add(0, -F::new(100_000_000));
+        assert_eq!(tree.get(0).value(), 0);
+        assert_eq!(tree.range_sum(1, 3), F::new(500_000_000));
+    }
+
+    #[test]
+    fn f64_sums() {
+        let tree = FenwickTree::from(vec![0.5, 0.25, 0.125, 0.125]);
+        assert_eq!(tree.prefix_sum(4), 1.0);
+        assert_eq!(tree.range_sum(1, 3), 0.375);
+        assert_eq!(tree.lower_bound(0.7), Some(2));
+    }
+
+    #[test]
+    fn lower_bound_cases() {
+        let tree = FenwickTree::from(vec![3i32, 0, 1, 4, 0, 2]);
+        // Prefix sums: 0, 3, 3, 4, 8, 8, 10
+        assert_eq!(tree.lower_bound(-5), Some(0));
+        assert_eq!(tree.lower_bound(0), Some(0));
+        assert_eq!(tree.lower_bound(1), Some(1));
+        assert_eq!(tree.lower_bound(3), Some(1));
+        assert_eq!(tree.lower_bound(4), Some(3));
+        assert_eq!(tree.lower_bound(5), Some(4));
+        assert_eq!(tree.lower_bound(9), Some(6));
+        assert_eq!(tree.lower_bound(10), Some(6));
+        assert_eq!(tree.lower_bound(11), None);
+        assert_eq!(FenwickTree::<i32>::new(0).lower_bound(1), None);
+    }
+
+    #[test]
+    fn rect_sums_agree_with_model() {
+        let (rows, cols) = (7, 9);
+        let mut rng = Rng::new(0xF2D);
+        let mut tree = FenwickTree2D::new(rows, cols);
+        let mut model = vec![vec![0i64; cols]; rows];
+        for _ in 0..300 {
+            let (r, c) = (
+                rng.below(rows as u64) as usize,
+                rng.below(cols as u64) as usize,
+            );
+            let x = rng.symmetric(50) as i64;
+            tree.add(r, c, x);
+            model[r][c] += x;
+
+            let r1 = rng.below(rows as u64 + 1) as usize;
+            let r2 = r1 + rng.below((rows - r1) as u64 + 1) as usize;
+            let c1 = rng.below(cols as u64 + 1) as usize;
+            let c2 = c1 + rng.below((cols - c1) as u64 + 1) as usize;
+            let expected: i64 = model[r1..r2]
+                .iter()
+                .map(|row| row[c1..c2].iter().sum::<i64>())
+                .sum();
+            assert_eq!(tree.rect_sum(r1, c1, r2, c2), expected);
+        }
+    }
+
+    #[test]
+    fn range_updates_agree_with_model() {
+        let n = 40;
+        let mut rng = Rng::new(0x4A6E);
+        let mut tree = RangeFenwickTree::new(n);
+        let mut model = vec![0i64; n];
+        for _ in 0..300 {
+            let l = rng.below(n as u64 + 1) as usize;
+            let r = l + rng.below((n - l) as u64 + 1) as usize;
+            let x = rng.symmetric(100) as i64;
+            if rng.below(2) == 0 {
+                tree.range_add(l, r, x);
+                model[l..r].iter_mut().for_each(|y| *y += x);
+            } else {
+                assert_eq!(tree.range_sum(l, r), model[l..r].iter().sum::<i64>());
+            }
+        }
+        for (i, &x) in model.iter().enumerate() {
+            assert_eq!(tree.get(i), x);
+        }
+    }
+
+    #[test]
+    fn times_is_repeated_addition() {
+        assert_eq!(7i64.times(0), 0);
+        assert_eq!((-3i8).times(5), -15);
+        assert_eq!(F::new(2).times(1 << 40), F::new(2) * F::new(1 << 40));
+    }
+
+    #[test]
+    fn times_near_the_limits() {
+        // The result fits even though doubling the base once more would not
+        assert_eq!((1i32 << 20).times(1024), 1 << 30);
+        assert_eq!(i8::MAX.times(1), i8::MAX);
+        assert_eq!((-64i8).times(2), i8::MIN);
+        let r = Rational::new(1 << 52, 3).unwrap();
+        assert_eq!(r.times(1 << 10), Rational::new(1 << 62, 3).unwrap());
+
+        let mut tree = RangeFenwickTree::<i32>::new(2048);
+        tree.range_add(0, 1024, 1 << 20);
+        assert_eq!(tree.prefix_sum(1024), 1 << 30);
+    }
+}
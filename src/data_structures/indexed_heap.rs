@@ -0,0 +1,401 @@
+/*!
+Data Structures
+
+Indexed Binary Heap
+
+Implements:
+- [IndexedHeap], with the aliases [IndexedMaxHeap] and [IndexedMinHeap]
+
+A priority queue of `(index, key)` pairs with at most one entry per index, where the key of
+any index can be changed or removed in `O(log n)`, as needed by Dijkstra's algorithm.
+
+The heap array holds indices, and a position map records where in the heap array each index
+is. Both are kept mutually consistent by the shared sift-up and sift-down of
+[heap_sort](crate::sorting::heap_sort), which report every swap back to the heap.
+*/
+
+use crate::data_structures::priority_queue::{Max, Min, Order};
+use crate::sorting::heap_sort::{Sift, sift_down_with, sift_up_with};
+#[cfg(any(debug_assertions, feature = "checked"))]
+use contracts::invariant;
+use std::fmt;
+use std::marker::PhantomData;
+
+/**
+Indexed Binary Heap
+
+A priority queue whose entries are addressed by an index `0, 1, 2, ...`. Memory is linear in
+the largest index used, so indices should be dense, such as the vertices of a graph.
+*/
+pub struct IndexedHeap<T, O = Max> {
+    /// `heap[k]` is the index at heap position `k`
+    heap: Vec<usize>,
+    /// `pos[i]` is the heap position of index `i`, if present
+    pos: Vec<Option<usize>>,
+    /// `keys[i]` is the key of index `i`, if present
+    keys: Vec<Option<T>>,
+    order: PhantomData<O>,
+}
+
+/// An [IndexedHeap] with the greatest key on top
+pub type IndexedMaxHeap<T> = IndexedHeap<T, Max>;
+
+/// An [IndexedHeap] with the least key on top
+pub type IndexedMinHeap<T> = IndexedHeap<T, Min>;
+
+impl<T: Ord, O: Order> Sift for IndexedHeap<T, O> {
+    #[cfg(any(debug_assertions, feature = "checked"))]
+    fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    fn below(&self, i: usize, j: usize) -> bool {
+        O::below(self.key_at(i), self.key_at(j))
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.heap.swap(i, j);
+        self.pos[self.heap[i]] = Some(i);
+        self.pos[self.heap[j]] = Some(j);
+    }
+}
+
+impl<T: Ord, O: Order> IndexedHeap<T, O> {
+    /// Creates an empty heap
+    pub fn new() -> Self {
+        IndexedHeap {
+            heap: Vec::new(),
+            pos: Vec::new(),
+            keys: Vec::new(),
+            order: PhantomData,
+        }
+    }
+
+    /// Creates an empty heap with space for the indices `0..capacity`
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut heap = Self::new();
+        heap.reserve_index(capacity);
+        heap
+    }
+
+    /// Key at heap position `k`
+    fn key_at(&self, k: usize) -> &T {
+        self.keys[self.heap[k]]
+            .as_ref()
+            .expect("every index in the heap has a key")
+    }
+
+    /// Whether the heap array and the position map describe each other
+    #[cfg(any(debug_assertions, feature = "checked"))]
+    fn is_consistent(&self) -> bool {
+        self.pos.len() == self.keys.len()
+            && self.heap.iter().enumerate().all(|(k, &i)| {
+                i < self.pos.len() && self.pos[i] == Some(k) && self.keys[i].is_some()
+            })
+            && self.pos.iter().filter(|p| p.is_some()).count() == self.heap.len()
+            && self.keys.iter().filter(|x| x.is_some()).count() == self.heap.len()
+    }
+
+    /// Whether no key belongs below the key of its child
+    #[cfg(any(debug_assertions, feature = "checked"))]
+    fn is_heap(&self) -> bool {
+        (1..self.heap.len()).all(|k| !self.below((k - 1) / 2, k))
+    }
+
+    /// Makes room for the indices `0..n`
+    fn reserve_index(&mut self, n: usize) {
+        if n > self.pos.len() {
+            self.pos.resize(n, None);
+            self.keys.resize_with(n, || None);
+        }
+    }
+
+    /// Restores the heap property after the key at heap position `k` changed
+    fn fix(&mut self, k: usize) {
+        let index = self.heap[k];
+        sift_up_with::<2, _>(self, k);
+        // Only one of the two moves the entry; if it moved up, this is a no-op
+        let k = self.pos[index].expect("position map is consistent");
+        let end = self.heap.len() - 1;
+        sift_down_with::<2, _>(self, k, end);
+    }
+}
+
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::invariant(self.is_consistent()),
+    contracts::invariant(self.is_heap())
+)]
+impl<T: Ord, O: Order> IndexedHeap<T, O> {
+    /// Number of entries in the heap
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Whether the heap is empty
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Whether `index` has an entry
+    ///
+    /// Time Complexity: `O(1)`
+    pub fn contains(&self, index: usize) -> bool {
+        self.pos.get(index).is_some_and(|p| p.is_some())
+    }
+
+    /// Returns the key of `index`, if present
+    ///
+    /// Time Complexity: `O(1)`
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.keys.get(index).and_then(|x| x.as_ref())
+    }
+
+    /// Returns the top entry, or `None` if the heap is empty
+    ///
+    /// Time Complexity: `O(1)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(ret.is_none_or(|(i, _)| self.pos[i] == Some(0)))
+    )]
+    pub fn peek(&self) -> Option<(usize, &T)> {
+        let &i = self.heap.first()?;
+        Some((i, self.key_at(0)))
+    }
+
+    /// Sets the key of `index` to `key`, returning its previous key if it had one
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(self.contains(index)),
+        contracts::ensures(self.len() == old(self.len()) + ret.is_none() as usize)
+    )]
+    pub fn insert(&mut self, index: usize, key: T) -> Option<T> {
+        self.reserve_index(index + 1);
+        match self.pos[index] {
+            Some(k) => {
+                let previous = self.keys[index].replace(key);
+                self.fix(k);
+                previous
+            }
+            None => {
+                let k = self.heap.len();
+                self.heap.push(index);
+                self.pos[index] = Some(k);
+                self.keys[index] = Some(key);
+                sift_up_with::<2, _>(self, k);
+                None
+            }
+        }
+    }
+
+    /// Removes and returns the top entry, or `None` if the heap is empty
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(self.len() == old(self.len()).saturating_sub(1)),
+        contracts::ensures(ret.as_ref().is_none_or(|(i, _)| !self.contains(*i)))
+    )]
+    pub fn pop(&mut self) -> Option<(usize, T)> {
+        let &index = self.heap.first()?;
+        let key = self.remove(index)?;
+        Some((index, key))
+    }
+
+    /// Removes the entry of `index`, returning its key if it had one
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(!self.contains(index)),
+        contracts::ensures(self.len() == old(self.len()) - ret.is_some() as usize)
+    )]
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        let k = (*self.pos.get(index)?)?;
+        let last = self.heap.len() - 1;
+        self.swap(k, last);
+        self.heap.pop();
+        self.pos[index] = None;
+        let key = self.keys[index].take();
+        if k < last {
+            // The entry moved into the hole may belong higher or lower
+            self.fix(k);
+        }
+        key
+    }
+
+    /// Lowers the key of `index` to `key`. Panics if `index` has no entry.
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(self.get(index).is_some_and(|old| key <= *old))
+    )]
+    pub fn decrease_key(&mut self, index: usize, key: T) {
+        self.change_key(index, key);
+    }
+
+    /// Raises the key of `index` to `key`. Panics if `index` has no entry.
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(self.get(index).is_some_and(|old| key >= *old))
+    )]
+    pub fn increase_key(&mut self, index: usize, key: T) {
+        self.change_key(index, key);
+    }
+
+    /// Sets the key of `index` to `key`. Panics if `index` has no entry.
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(self.contains(index))
+    )]
+    pub fn change_key(&mut self, index: usize, key: T) {
+        let k = self
+            .pos
+            .get(index)
+            .copied()
+            .flatten()
+            .expect("change_key: index has no entry");
+        self.keys[index] = Some(key);
+        self.fix(k);
+    }
+
+    /// Removes all entries
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(self.is_empty())
+    )]
+    pub fn clear(&mut self) {
+        self.heap.clear();
+        self.pos.iter_mut().for_each(|p| *p = None);
+        self.keys.iter_mut().for_each(|x| *x = None);
+    }
+}
+
+impl<T: Ord, O: Order> Default for IndexedHeap<T, O> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, O> Clone for IndexedHeap<T, O> {
+    fn clone(&self) -> Self {
+        IndexedHeap {
+            heap: self.heap.clone(),
+            pos: self.pos.clone(),
+            keys: self.keys.clone(),
+            order: PhantomData,
+        }
+    }
+}
+
+impl<T: fmt::Debug, O> fmt::Debug for IndexedHeap<T, O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.heap.iter().map(|&i| (i, &self.keys[i])))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{ENABLED, Kind, catch};
+    use crate::sorting::prop_test::Rng;
+
+    #[test]
+    fn pops_in_key_order() {
+        let mut heap = IndexedMinHeap::new();
+        for (i, key) in [(3, 30), (0, 5), (7, 12), (1, 40)] {
+            assert_eq!(heap.insert(i, key), None);
+        }
+        assert_eq!(heap.len(), 4);
+        assert_eq!(heap.peek(), Some((0, &5)));
+        assert!(heap.contains(7) && !heap.contains(2) && !heap.contains(100));
+
+        heap.decrease_key(1, 1);
+        heap.increase_key(0, 50);
+        assert_eq!(heap.insert(7, 20), Some(12));
+        assert_eq!(heap.remove(3), Some(30));
+        assert_eq!(heap.remove(3), None);
+
+        let mut popped = Vec::new();
+        while let Some(entry) = heap.pop() {
+            popped.push(entry);
+        }
+        assert_eq!(popped, [(1, 1), (7, 20), (0, 50)]);
+    }
+
+    #[test]
+    fn dijkstra() {
+        // (from, to, weight)
+        let edges = [(0, 1, 7), (0, 2, 9), (0, 5, 14), (1, 2, 10), (1, 3, 15)];
+        let edges = edges
+            .iter()
+            .chain(&[(2, 3, 11), (2, 5, 2), (3, 4, 6), (4, 5, 9)]);
+        let mut adjacency = vec![Vec::new(); 6];
+        for &(u, v, w) in edges {
+            adjacency[u].push((v, w));
+            adjacency[v].push((u, w));
+        }
+
+        let mut dist = [u64::MAX; 6];
+        let mut queue = IndexedMinHeap::with_capacity(6);
+        dist[0] = 0;
+        queue.insert(0, 0);
+        while let Some((u, d)) = queue.pop() {
+            for &(v, w) in &adjacency[u] {
+                if d + w < dist[v] {
+                    if queue.contains(v) {
+                        queue.decrease_key(v, d + w);
+                    } else {
+                        queue.insert(v, d + w);
+                    }
+                    dist[v] = d + w;
+                }
+            }
+        }
+        assert_eq!(dist, [0, 7, 9, 20, 20, 11]);
+    }
+
+    #[test]
+    fn random_operations_agree_with_model() {
+        let mut heap = IndexedMaxHeap::new();
+        let mut model: Vec<Option<i64>> = vec![None; 50];
+        let mut rng = Rng::new(99);
+        for _ in 0..2000 {
+            let index = rng.below(50) as usize;
+            let key = rng.below(1000) as i64;
+            match rng.below(4) {
+                0 => assert_eq!(heap.remove(index), model[index].take()),
+                1 => {
+                    let top = model.iter().flatten().max().copied();
+                    let popped = heap.pop();
+                    assert_eq!(popped.as_ref().map(|&(_, k)| k), top);
+                    if let Some((i, _)) = popped {
+                        model[i] = None;
+                    }
+                }
+                _ => assert_eq!(heap.insert(index, key), model[index].replace(key)),
+            }
+            assert_eq!(heap.len(), model.iter().flatten().count());
+        }
+    }
+
+    #[test]
+    fn wrong_direction_violates_precondition() {
+        let mut heap = IndexedMinHeap::new();
+        heap.insert(0, 10);
+        let result = catch(move || heap.decrease_key(0, 20));
+        assert_eq!(result.is_err(), ENABLED);
+        if let Err(v) = result {
+            assert_eq!(v.kind, Kind::Precondition);
+        }
+    }
+}
@@ -0,0 +1,299 @@
+/*!
+Data Structures
+
+Min-Max Heap
+
+Implements:
+- [MinMaxHeap]
+
+A binary heap stored in a [Vec] whose levels alternate between min levels (starting with the
+root) and max levels: an element on a min level is the least of its subtree, and one on a
+max level the greatest. The least element is the root, and the greatest one of its children.
+*/
+
+use crate::data_structures::priority_queue::{Max, Order, PriorityQueue};
+#[cfg(any(debug_assertions, feature = "checked"))]
+use crate::sorting::helper::{ge_seg, is_min_max_heap, le_seg};
+#[cfg(any(debug_assertions, feature = "checked"))]
+use contracts::invariant;
+use std::fmt;
+use std::marker::PhantomData;
+
+/**
+Min-Max Heap
+
+A double-ended priority queue: both the least and the greatest element can be read in `O(1)`
+and removed in `O(log n)`. As a [PriorityQueue], its top is the end selected by `O`.
+*/
+pub struct MinMaxHeap<T, O = Max> {
+    data: Vec<T>,
+    order: PhantomData<O>,
+}
+
+/// Whether index `i` is on a min level; the root is on level 0
+fn is_min_level(i: usize) -> bool {
+    (i + 1).ilog2().is_multiple_of(2)
+}
+
+impl<T: Ord, O: Order> MinMaxHeap<T, O> {
+    /// Creates an empty heap
+    pub fn new() -> Self {
+        MinMaxHeap {
+            data: Vec::new(),
+            order: PhantomData,
+        }
+    }
+
+    /// Index of the greatest element, if any
+    fn max_index(&self) -> Option<usize> {
+        match self.data.len() {
+            0 => None,
+            1 => Some(0),
+            2 => Some(1),
+            _ => Some(if self.data[1] >= self.data[2] { 1 } else { 2 }),
+        }
+    }
+
+    /// Whether `a` belongs above `b` on the kind of level of index `i`
+    fn above(&self, i: usize, a: usize, b: usize) -> bool {
+        if is_min_level(i) {
+            self.data[a] < self.data[b]
+        } else {
+            self.data[a] > self.data[b]
+        }
+    }
+
+    /// Moves the new element at `i` up, first to the right kind of level, then along
+    /// its grandparents
+    fn bubble_up(&mut self, mut i: usize) {
+        if i == 0 {
+            return;
+        }
+        let parent = (i - 1) / 2;
+        // If it belongs on the parent's kind of level, swap them and continue from there
+        if self.above(parent, i, parent) {
+            self.data.swap(i, parent);
+            i = parent;
+        }
+        while i > 2 {
+            let grandparent = ((i - 1) / 2 - 1) / 2;
+            if !self.above(i, i, grandparent) {
+                break;
+            }
+            self.data.swap(i, grandparent);
+            i = grandparent;
+        }
+    }
+
+    /// Moves the element at `i` down until its subtree is a min-max heap again,
+    /// given that the subtrees of its children are
+    fn trickle_down(&mut self, mut i: usize) {
+        let n = self.data.len();
+        loop {
+            // The child or grandchild that belongs highest on the level of `i`
+            let mut best = None;
+            let first_child = 2 * i + 1;
+            let descendants = [first_child, first_child + 1]
+                .into_iter()
+                .chain(4 * i + 3..4 * i + 7)
+                .filter(|&j| j < n);
+            for j in descendants {
+                if best.is_none_or(|b| self.above(i, j, b)) {
+                    best = Some(j);
+                }
+            }
+            let Some(m) = best else { return };
+            if !self.above(i, m, i) {
+                return;
+            }
+            self.data.swap(m, i);
+            if m <= first_child + 1 {
+                // A child is on the other kind of level and has no descendants to fix
+                return;
+            }
+            // A grandchild: its parent may now belong above it
+            let parent = (m - 1) / 2;
+            if self.above(parent, m, parent) {
+                self.data.swap(m, parent);
+            }
+            i = m;
+        }
+    }
+
+    /// Removes the element at `i`, which must exist, refilling it from the end
+    fn remove_at(&mut self, i: usize) -> T {
+        let x = self.data.swap_remove(i);
+        if i < self.data.len() {
+            self.trickle_down(i);
+        }
+        x
+    }
+}
+
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::invariant(is_min_max_heap(&self.data))
+)]
+impl<T: Ord, O: Order> MinMaxHeap<T, O> {
+    /// Number of elements in the heap
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Whether the heap is empty
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the least element, or `None` if the heap is empty
+    ///
+    /// Time Complexity: `O(1)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(ret.is_none_or(|x| le_seg(x, &self.data)))
+    )]
+    pub fn peek_min(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    /// Returns the greatest element, or `None` if the heap is empty
+    ///
+    /// Time Complexity: `O(1)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(ret.is_none_or(|x| ge_seg(x, &self.data)))
+    )]
+    pub fn peek_max(&self) -> Option<&T> {
+        self.max_index().map(|i| &self.data[i])
+    }
+
+    /// Inserts `x`
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(self.len() == old(self.len()) + 1)
+    )]
+    pub fn push(&mut self, x: T) {
+        self.data.push(x);
+        let last = self.data.len() - 1;
+        self.bubble_up(last);
+    }
+
+    /// Removes and returns the least element, or `None` if the heap is empty
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(self.len() == old(self.len()).saturating_sub(1)),
+        contracts::ensures(ret.as_ref().is_none_or(|x| le_seg(x, &self.data)))
+    )]
+    pub fn pop_min(&mut self) -> Option<T> {
+        if self.data.is_empty() {
+            return None;
+        }
+        Some(self.remove_at(0))
+    }
+
+    /// Removes and returns the greatest element, or `None` if the heap is empty
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(self.len() == old(self.len()).saturating_sub(1)),
+        contracts::ensures(ret.as_ref().is_none_or(|x| ge_seg(x, &self.data)))
+    )]
+    pub fn pop_max(&mut self) -> Option<T> {
+        let i = self.max_index()?;
+        Some(self.remove_at(i))
+    }
+
+    /// Returns the element on top according to `O`
+    pub fn peek(&self) -> Option<&T> {
+        match (self.peek_min(), self.peek_max()) {
+            (Some(min), Some(max)) if O::below(min, max) => Some(max),
+            (min, _) => min,
+        }
+    }
+
+    /// Removes and returns the element on top according to `O`
+    pub fn pop(&mut self) -> Option<T> {
+        let max_on_top = matches!(
+            (self.peek_min(), self.peek_max()),
+            (Some(min), Some(max)) if O::below(min, max)
+        );
+        if max_on_top {
+            self.pop_max()
+        } else {
+            self.pop_min()
+        }
+    }
+}
+
+impl<T: Ord, O: Order> PriorityQueue<T> for MinMaxHeap<T, O> {
+    type Order = O;
+
+    fn len(&self) -> usize {
+        MinMaxHeap::len(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        MinMaxHeap::peek(self)
+    }
+
+    fn push(&mut self, x: T) {
+        MinMaxHeap::push(self, x);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        MinMaxHeap::pop(self)
+    }
+}
+
+impl<T: Ord, O: Order> Default for MinMaxHeap<T, O> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, O> Clone for MinMaxHeap<T, O> {
+    fn clone(&self) -> Self {
+        MinMaxHeap {
+            data: self.data.clone(),
+            order: PhantomData,
+        }
+    }
+}
+
+impl<T: fmt::Debug, O> fmt::Debug for MinMaxHeap<T, O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.data).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structures::pq_test_helper::test_priority_queue;
+    use crate::data_structures::priority_queue::Min;
+
+    #[test]
+    fn test_min_max_heaps() {
+        test_priority_queue(MinMaxHeap::<i64>::new);
+        test_priority_queue(MinMaxHeap::<i64, Min>::new);
+    }
+
+    #[test]
+    fn pops_from_both_ends() {
+        let mut heap: MinMaxHeap<i32> = MinMaxHeap::new();
+        for x in [5, 1, 9, 3, 7, 3, 8, 2, 6, 4] {
+            heap.push(x);
+        }
+        let mut ends = Vec::new();
+        while let (Some(min), Some(max)) = (heap.pop_min(), heap.pop_max()) {
+            ends.push((min, max));
+        }
+        assert_eq!(ends, [(1, 9), (2, 8), (3, 7), (3, 6), (4, 5)]);
+        assert!(heap.is_empty());
+    }
+}
@@ -0,0 +1,10 @@
+pub mod binary_heap;
+pub mod dary_heap;
+pub mod fenwick_tree;
+pub mod indexed_heap;
+pub mod min_max_heap;
+pub mod pairing_heap;
+mod pq_test_helper;
+pub mod priority_queue;
+pub mod segment_tree;
+pub mod union_find;
@@ -0,0 +1,265 @@
+/*!
+Data Structures
+
+Pairing Heap
+
+Implements:
+- [PairingHeap]
+
+A heap-ordered multiway tree. Pushing and melding only link two roots, the one belonging
+lower becoming a child of the other; popping merges the children of the root in two passes
+(pairs left to right, then the results right to left).
+*/
+
+use crate::data_structures::priority_queue::{Max, Order, PriorityQueue};
+#[cfg(any(debug_assertions, feature = "checked"))]
+use contracts::invariant;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+
+struct Node<T> {
+    value: T,
+    children: Vec<Node<T>>,
+}
+
+/**
+Pairing Heap
+
+A priority queue whose top element, the greatest for [Max] and the least for
+[Min](super::priority_queue::Min), can be read in `O(1)`, and which can absorb another
+pairing heap in `O(1)` with [meld](PairingHeap::meld).
+
+Push Time Complexity: `O(1)`
+Meld Time Complexity: `O(1)`
+Pop Time Complexity: `O(log n)` amortized
+*/
+pub struct PairingHeap<T, O = Max> {
+    root: Option<Node<T>>,
+    len: usize,
+    order: PhantomData<O>,
+}
+
+/// Makes the root that belongs lower a child of the other
+fn link<T: Ord, O: Order>(mut a: Node<T>, mut b: Node<T>) -> Node<T> {
+    if O::below(&a.value, &b.value) {
+        mem::swap(&mut a, &mut b);
+    }
+    a.children.push(b);
+    a
+}
+
+impl<T: Ord, O: Order> PairingHeap<T, O> {
+    /// Creates an empty heap
+    pub fn new() -> Self {
+        PairingHeap {
+            root: None,
+            len: 0,
+            order: PhantomData,
+        }
+    }
+
+    /// Whether no child belongs above its parent, and `len` counts the nodes.
+    /// Iterative, since the tree may be as deep as it is large.
+    #[cfg(any(debug_assertions, feature = "checked"))]
+    fn is_heap_ordered(&self) -> bool {
+        let mut count = 0;
+        let mut stack: Vec<&Node<T>> = self.root.iter().collect();
+        while let Some(node) = stack.pop() {
+            count += 1;
+            for child in &node.children {
+                if O::below(&node.value, &child.value) {
+                    return false;
+                }
+                stack.push(child);
+            }
+        }
+        count == self.len
+    }
+}
+
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::invariant(self.is_heap_ordered())
+)]
+impl<T: Ord, O: Order> PairingHeap<T, O> {
+    /// Number of elements in the heap
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the heap is empty
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Returns the top element, or `None` if the heap is empty
+    ///
+    /// Time Complexity: `O(1)`
+    pub fn peek(&self) -> Option<&T> {
+        self.root.as_ref().map(|root| &root.value)
+    }
+
+    /// Inserts `x`
+    ///
+    /// Time Complexity: `O(1)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(self.len() == old(self.len()) + 1)
+    )]
+    pub fn push(&mut self, x: T) {
+        let node = Node {
+            value: x,
+            children: Vec::new(),
+        };
+        self.root = Some(match self.root.take() {
+            Some(root) => link::<T, O>(root, node),
+            None => node,
+        });
+        self.len += 1;
+    }
+
+    /// Moves all elements of `other` into this heap
+    ///
+    /// Time Complexity: `O(1)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(self.len() == old(self.len()) + old(other.len()))
+    )]
+    pub fn meld(&mut self, mut other: Self) {
+        self.root = match (self.root.take(), other.root.take()) {
+            (Some(a), Some(b)) => Some(link::<T, O>(a, b)),
+            (a, b) => a.or(b),
+        };
+        self.len += mem::take(&mut other.len);
+    }
+
+    /// Removes and returns the top element, or `None` if the heap is empty
+    ///
+    /// Time Complexity: `O(log n)` amortized
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(self.len() == old(self.len()).saturating_sub(1)),
+        contracts::ensures(ret.as_ref().is_none_or(|top| self.peek().is_none_or(|x| !O::below(top, x))))
+    )]
+    pub fn pop(&mut self) -> Option<T> {
+        let Node { value, children } = self.root.take()?;
+        self.len -= 1;
+
+        // First pass: link pairs from left to right
+        let mut pairs = Vec::with_capacity(children.len().div_ceil(2));
+        let mut children = children.into_iter();
+        while let Some(a) = children.next() {
+            pairs.push(match children.next() {
+                Some(b) => link::<T, O>(a, b),
+                None => a,
+            });
+        }
+        // Second pass: link the pairs from right to left
+        self.root = pairs
+            .into_iter()
+            .rev()
+            .reduce(|acc, x| link::<T, O>(acc, x));
+        Some(value)
+    }
+}
+
+impl<T: Ord, O: Order> PriorityQueue<T> for PairingHeap<T, O> {
+    type Order = O;
+
+    fn len(&self) -> usize {
+        PairingHeap::len(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        PairingHeap::peek(self)
+    }
+
+    fn push(&mut self, x: T) {
+        PairingHeap::push(self, x);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        PairingHeap::pop(self)
+    }
+}
+
+impl<T: Ord, O: Order> Default for PairingHeap<T, O> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, O> Drop for PairingHeap<T, O> {
+    /// Frees the nodes iteratively, since the default recursive drop could overflow the stack
+    fn drop(&mut self) {
+        let mut stack: Vec<Node<T>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.append(&mut node.children);
+        }
+    }
+}
+
+impl<T: fmt::Debug, O> fmt::Debug for PairingHeap<T, O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut stack: Vec<&Node<T>> = self.root.iter().collect();
+        let mut list = f.debug_list();
+        while let Some(node) = stack.pop() {
+            list.entry(&node.value);
+            stack.extend(&node.children);
+        }
+        list.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structures::pq_test_helper::test_priority_queue;
+    use crate::data_structures::priority_queue::Min;
+
+    #[test]
+    fn test_pairing_heaps() {
+        test_priority_queue(PairingHeap::<i64>::new);
+        test_priority_queue(PairingHeap::<i64, Min>::new);
+    }
+
+    #[test]
+    fn meld() {
+        let mut a = PairingHeap::<i32, Min>::new();
+        let mut b = PairingHeap::new();
+        for x in [5, 1, 9] {
+            a.push(x);
+        }
+        for x in [4, 0, 7] {
+            b.push(x);
+        }
+        a.meld(b);
+        a.meld(PairingHeap::new());
+        assert_eq!(a.len(), 6);
+        let popped: Vec<i32> = std::iter::from_fn(|| a.pop()).collect();
+        assert_eq!(popped, [0, 1, 4, 5, 7, 9]);
+    }
+
+    #[test]
+    fn deep_tree_drops_without_overflow() {
+        // The shape left by ascending pushes into a max-heap: every old root is a child of
+        // the new one. Built directly, since checking the invariant on each push is quadratic.
+        let mut root = Node {
+            value: 0u32,
+            children: Vec::new(),
+        };
+        for x in 1..200_000 {
+            root = Node {
+                value: x,
+                children: vec![root],
+            };
+        }
+        let heap = PairingHeap::<u32> {
+            root: Some(root),
+            len: 200_000,
+            order: PhantomData,
+        };
+        assert_eq!(heap.peek(), Some(&199_999));
+    }
+}
@@ -0,0 +1,91 @@
+#![cfg(test)]
+use crate::data_structures::priority_queue::{Order, PriorityQueue};
+use crate::sorting::prop_test::Rng;
+
+// ---- Internal helpers ----
+
+/// The element of `model` that belongs on top according to `O`
+fn model_top<O: Order>(model: &[i64]) -> Option<i64> {
+    model
+        .iter()
+        .copied()
+        .reduce(|top, x| if O::below(&top, &x) { x } else { top })
+}
+
+/// Pushes all of `input`, then pops everything and checks the order
+fn push_all_then_pop_all<Q: PriorityQueue<i64>>(queue: &mut Q, input: &[i64]) {
+    for &x in input {
+        queue.push(x);
+    }
+    assert_eq!(queue.len(), input.len());
+
+    let mut popped = Vec::with_capacity(input.len());
+    while let Some(x) = queue.pop() {
+        popped.push(x);
+    }
+    assert!(queue.is_empty());
+    assert_eq!(queue.peek(), None);
+
+    let mut expected = input.to_vec();
+    expected.sort();
+    let mut sorted = popped.clone();
+    sorted.sort();
+    assert_eq!(sorted, expected, "popped elements are not the pushed ones");
+    assert!(
+        popped.windows(2).all(|w| !Q::Order::below(&w[0], &w[1])),
+        "popped out of order: {:?}",
+        popped
+    );
+}
+
+/// Runs random pushes and pops against an unsorted model
+fn interleaved<Q: PriorityQueue<i64>>(queue: &mut Q, rng: &mut Rng, ops: usize, range: isize) {
+    let mut model = Vec::new();
+    for _ in 0..ops {
+        if rng.below(3) == 0 {
+            let expected = model_top::<Q::Order>(&model);
+            assert_eq!(queue.peek().copied(), expected);
+            assert_eq!(queue.pop(), expected);
+            if let Some(top) = expected {
+                let i = model.iter().position(|&x| x == top).unwrap();
+                model.swap_remove(i);
+            }
+        } else {
+            let x = rng.symmetric(range) as i64;
+            queue.push(x);
+            model.push(x);
+        }
+        assert_eq!(queue.len(), model.len());
+    }
+}
+
+// ---- Public API ----
+
+/// Checks a priority queue against a model on fixed and random workloads.
+/// `new` must return an empty queue.
+pub fn test_priority_queue<Q: PriorityQueue<i64>>(new: impl Fn() -> Q) {
+    let ascending: Vec<i64> = (0..100).collect();
+    let descending: Vec<i64> = (0..100).rev().collect();
+    let duplicates: Vec<i64> = (0..100).map(|i| i % 3).collect();
+    let extremes = [i64::MAX, i64::MIN, 0, i64::MAX, i64::MIN];
+    for input in [
+        &[][..],
+        &[42],
+        &ascending,
+        &descending,
+        &duplicates,
+        &extremes,
+    ] {
+        push_all_then_pop_all(&mut new(), input);
+    }
+
+    let mut rng = Rng::new(0x5AFE_4EAF);
+    for range in [2, 1000] {
+        interleaved(&mut new(), &mut rng, 1000, range);
+    }
+
+    // Reuse after being emptied
+    let mut queue = new();
+    push_all_then_pop_all(&mut queue, &duplicates);
+    push_all_then_pop_all(&mut queue, &descending);
+}
@@ -0,0 +1,65 @@
+/*!
+Data Structures
+
+Priority Queues
+
+Implements:
+- [PriorityQueue], the interface shared by the heaps of this module
+- [Order], with [Max] and [Min], selecting which element is on top
+
+Implemented by:
+- [BinaryHeap](crate::data_structures::binary_heap::BinaryHeap)
+- [DaryHeap](crate::data_structures::dary_heap::DaryHeap)
+- [PairingHeap](crate::data_structures::pairing_heap::PairingHeap)
+- [MinMaxHeap](crate::data_structures::min_max_heap::MinMaxHeap)
+*/
+
+/// Which element of a heap is on top
+pub trait Order {
+    /// Whether `a` may not be the parent of `b`, i.e. `b` has the higher priority
+    fn below<T: Ord>(a: &T, b: &T) -> bool;
+}
+
+/// The greatest element is on top
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Max;
+
+/// The least element is on top
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Min;
+
+impl Order for Max {
+    fn below<T: Ord>(a: &T, b: &T) -> bool {
+        a < b
+    }
+}
+
+impl Order for Min {
+    fn below<T: Ord>(a: &T, b: &T) -> bool {
+        a > b
+    }
+}
+
+/// A collection whose top element, as selected by [PriorityQueue::Order],
+/// can be read and removed.
+pub trait PriorityQueue<T: Ord> {
+    /// Which element is on top
+    type Order: Order;
+
+    /// Number of elements
+    fn len(&self) -> usize;
+
+    /// Whether there are no elements
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the top element, or `None` if there are no elements
+    fn peek(&self) -> Option<&T>;
+
+    /// Inserts `x`
+    fn push(&mut self, x: T);
+
+    /// Removes and returns the top element, or `None` if there are no elements
+    fn pop(&mut self) -> Option<T>;
+}
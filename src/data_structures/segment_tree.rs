@@ -0,0 +1,924 @@
+/*!
+Data Structures
+
+Segment Tree
+
+Implements:
+- [Monoid], with [Sum], [Minimum], [Maximum] and [Affine]
+- [Action], a monoid of maps acting on another monoid
+- [SegmentTree], with point updates and range queries
+- [LazySegmentTree], with range updates and range queries
+
+Both trees are stored as an implicit perfect binary tree in a [Vec], the leaves padded with
+the identity to a power of two, where node `k` holds the product of its children `2k` and
+`2k + 1`. A range `l..r` is covered by `O(log n)` nodes, found bottom-up.
+*/
+
+use crate::data_structures::fenwick_tree::AdditiveGroup;
+use crate::number_theory::field::Field;
+use std::fmt;
+use std::marker::PhantomData;
+
+/// A set [S](Monoid::S) with an associative operation [op](Monoid::op) and its identity:
+/// `op(a, op(b, c)) == op(op(a, b), c)` and `op(identity, a) == a == op(a, identity)`.
+/// The operation need not be commutative.
+pub trait Monoid {
+    /// The elements
+    type S: Clone;
+
+    /// The identity of [op](Monoid::op)
+    fn identity() -> Self::S;
+
+    /// The associative operation
+    fn op(a: &Self::S, b: &Self::S) -> Self::S;
+}
+
+/// A monoid [F](Action::F) of maps acting on the elements of [M](Action::M), such that
+/// every map distributes over the operation: `apply(f, op(a, b)) == op(apply(f, a), apply(f, b))`.
+pub trait Action {
+    /// The monoid acted on
+    type M: Monoid;
+
+    /// The maps
+    type F: Clone;
+
+    /// The map leaving every element unchanged
+    fn identity_map() -> Self::F;
+
+    /// `f ∘ g`, the map applying `g`, then `f`
+    fn compose(f: &Self::F, g: &Self::F) -> Self::F;
+
+    /// The image of `x` under `f`
+    fn apply(f: &Self::F, x: &<Self::M as Monoid>::S) -> <Self::M as Monoid>::S;
+}
+
+/// Types with a least and a greatest value, the identities of [Maximum] and [Minimum]
+pub trait Bounded {
+    const MIN: Self;
+    const MAX: Self;
+}
+
+macro_rules! impl_bounded {
+    ($($t:ty),*) => {$(
+        impl Bounded for $t {
+            const MIN: Self = <$t>::MIN;
+            const MAX: Self = <$t>::MAX;
+        }
+    )*};
+}
+
+impl_bounded!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
+
+/// Addition, with identity zero
+pub struct Sum<G>(PhantomData<G>);
+
+impl<G: AdditiveGroup> Monoid for Sum<G> {
+    type S = G;
+
+    fn identity() -> G {
+        G::zero()
+    }
+
+    fn op(a: &G, b: &G) -> G {
+        *a + *b
+    }
+}
+
+/// The least of two elements, with identity the greatest value
+pub struct Minimum<T>(PhantomData<T>);
+
+impl<T: Ord + Clone + Bounded> Monoid for Minimum<T> {
+    type S = T;
+
+    fn identity() -> T {
+        T::MAX
+    }
+
+    fn op(a: &T, b: &T) -> T {
+        a.min(b).clone()
+    }
+}
+
+/// The greatest of two elements, with identity the least value
+pub struct Maximum<T>(PhantomData<T>);
+
+impl<T: Ord + Clone + Bounded> Monoid for Maximum<T> {
+    type S = T;
+
+    fn identity() -> T {
+        T::MIN
+    }
+
+    fn op(a: &T, b: &T) -> T {
+        a.max(b).clone()
+    }
+}
+
+/// The map `x ↦ a·x + b` over a field
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AffineMap<F> {
+    pub a: F,
+    pub b: F,
+}
+
+impl<F: Field> AffineMap<F> {
+    /// The image of `x`
+    pub fn apply(&self, x: F) -> F {
+        self.a * x + self.b
+    }
+}
+
+/// Composition of affine maps in sequence order: `op(f, g)` applies `f`, then `g`,
+/// so a range query returns the maps of the range composed from left to right
+pub struct Affine<F>(PhantomData<F>);
+
+impl<F: Field> Monoid for Affine<F> {
+    type S = AffineMap<F>;
+
+    fn identity() -> AffineMap<F> {
+        AffineMap {
+            a: F::one(),
+            b: F::zero(),
+        }
+    }
+
+    fn op(f: &AffineMap<F>, g: &AffineMap<F>) -> AffineMap<F> {
+        AffineMap {
+            a: g.a * f.a,
+            b: g.a * f.b + g.b,
+        }
+    }
+}
+
+/// The number of leaves and levels of a tree over `n` elements
+fn shape(n: usize) -> (usize, u32) {
+    let size = n.next_power_of_two();
+    (size, size.trailing_zeros())
+}
+
+/**
+Segment Tree
+
+An array of `n` monoid elements supporting point updates and the product of any range, as
+well as binary searches for the longest range from an index whose product satisfies a
+predicate.
+
+Update Time Complexity: `O(log n)`
+Query Time Complexity: `O(log n)`
+Space Complexity: `O(n)`
+*/
+pub struct SegmentTree<M: Monoid> {
+    n: usize,
+    size: usize,
+    log: u32,
+    /// Node `k` has the children `2k` and `2k + 1`; the leaves start at `size`
+    data: Vec<M::S>,
+}
+
+impl<M: Monoid> SegmentTree<M> {
+    /// Creates a tree of `n` identities
+    pub fn new(n: usize) -> Self {
+        vec![M::identity(); n].into()
+    }
+
+    /// Recomputes node `k` from its children
+    fn update(&mut self, k: usize) {
+        self.data[k] = M::op(&self.data[2 * k], &self.data[2 * k + 1]);
+    }
+
+    /// Number of elements
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    /// Whether there are no elements
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Sets element `p` to `x`
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(p < self.len())
+    )]
+    pub fn set(&mut self, p: usize, x: M::S) {
+        let p = p + self.size;
+        self.data[p] = x;
+        for i in 1..=self.log {
+            self.update(p >> i);
+        }
+    }
+
+    /// Returns element `p`
+    ///
+    /// Time Complexity: `O(1)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(p < self.len())
+    )]
+    pub fn get(&self, p: usize) -> &M::S {
+        &self.data[p + self.size]
+    }
+
+    /// The product of the elements `l..r`, in order
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(l <= r && r <= self.len())
+    )]
+    pub fn prod(&self, l: usize, r: usize) -> M::S {
+        let (mut l, mut r) = (l + self.size, r + self.size);
+        let mut left = M::identity();
+        let mut right = M::identity();
+        while l < r {
+            if l & 1 == 1 {
+                left = M::op(&left, &self.data[l]);
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                right = M::op(&self.data[r], &right);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+        M::op(&left, &right)
+    }
+
+    /// The product of all elements
+    ///
+    /// Time Complexity: `O(1)`
+    pub fn all_prod(&self) -> M::S {
+        self.data[1].clone()
+    }
+
+    /// The greatest `r` such that `pred(prod(l, r))` holds, given that `pred` holds for the
+    /// identity and is monotone: once false on a range from `l`, it stays false on longer ones.
+    /// Then `pred` holds exactly on the ranges `l..r'` for `r' <= r`.
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(l <= self.len()),
+        contracts::requires(pred(&M::identity())),
+        contracts::ensures(l <= ret && ret <= self.len()),
+        contracts::ensures(pred(&self.prod(l, ret))),
+        contracts::ensures(ret == self.len() || !pred(&self.prod(l, ret + 1)))
+    )]
+    pub fn max_right<P: Fn(&M::S) -> bool>(&self, l: usize, pred: P) -> usize {
+        if l == self.n {
+            return self.n;
+        }
+        let mut l = l + self.size;
+        let mut acc = M::identity();
+        loop {
+            // Climb to the largest node starting at l
+            while l.is_multiple_of(2) {
+                l >>= 1;
+            }
+            let next = M::op(&acc, &self.data[l]);
+            if !pred(&next) {
+                // The answer is inside this node: descend, taking left children that fit
+                while l < self.size {
+                    l *= 2;
+                    let next = M::op(&acc, &self.data[l]);
+                    if pred(&next) {
+                        acc = next;
+                        l += 1;
+                    }
+                }
+                return l - self.size;
+            }
+            acc = next;
+            l += 1;
+            if l.is_power_of_two() {
+                return self.n;
+            }
+        }
+    }
+
+    /// The least `l` such that `pred(prod(l, r))` holds, given that `pred` holds for the
+    /// identity and is monotone: once false on a range ending at `r`, it stays false on
+    /// longer ones. Then `pred` holds exactly on the ranges `l'..r` for `l' >= l`.
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(r <= self.len()),
+        contracts::requires(pred(&M::identity())),
+        contracts::ensures(ret <= r),
+        contracts::ensures(pred(&self.prod(ret, r))),
+        contracts::ensures(ret == 0 || !pred(&self.prod(ret - 1, r)))
+    )]
+    pub fn min_left<P: Fn(&M::S) -> bool>(&self, r: usize, pred: P) -> usize {
+        if r == 0 {
+            return 0;
+        }
+        let mut r = r + self.size;
+        let mut acc = M::identity();
+        loop {
+            // Climb to the largest node ending at r
+            r -= 1;
+            while r > 1 && !r.is_multiple_of(2) {
+                r >>= 1;
+            }
+            let next = M::op(&self.data[r], &acc);
+            if !pred(&next) {
+                // The answer is inside this node: descend, taking right children that fit
+                while r < self.size {
+                    r = 2 * r + 1;
+                    let next = M::op(&self.data[r], &acc);
+                    if pred(&next) {
+                        acc = next;
+                        r -= 1;
+                    }
+                }
+                return r + 1 - self.size;
+            }
+            acc = next;
+            if r.is_power_of_two() {
+                return 0;
+            }
+        }
+    }
+}
+
+impl<M: Monoid> From<Vec<M::S>> for SegmentTree<M> {
+    /// Builds the tree over the given elements
+    ///
+    /// Time Complexity: `O(n)`
+    fn from(values: Vec<M::S>) -> Self {
+        let n = values.len();
+        let (size, log) = shape(n);
+        let mut data = vec![M::identity(); 2 * size];
+        data[size..size + n].clone_from_slice(&values);
+        let mut tree = SegmentTree { n, size, log, data };
+        for k in (1..size).rev() {
+            tree.update(k);
+        }
+        tree
+    }
+}
+
+impl<M: Monoid> Clone for SegmentTree<M> {
+    fn clone(&self) -> Self {
+        SegmentTree {
+            n: self.n,
+            size: self.size,
+            log: self.log,
+            data: self.data.clone(),
+        }
+    }
+}
+
+impl<M: Monoid<S: fmt::Debug>> fmt::Debug for SegmentTree<M> {
+    /// Shows the elements
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let leaves = &self.data[self.size..self.size + self.n];
+        f.debug_list().entries(leaves).finish()
+    }
+}
+
+/**
+Lazy Segment Tree
+
+An array of `n` monoid elements supporting applying a map of an [Action] to every element of
+a range, and the product of any range. A map applied to a whole node is stored at the node
+and only pushed to its children when a later operation descends through it.
+
+Update Time Complexity: `O(log n)`
+Query Time Complexity: `O(log n)`
+Space Complexity: `O(n)`
+*/
+pub struct LazySegmentTree<A: Action> {
+    n: usize,
+    size: usize,
+    log: u32,
+    /// Node `k` has the children `2k` and `2k + 1`; the leaves start at `size`
+    data: Vec<<A::M as Monoid>::S>,
+    /// The map still to be applied to the children of each internal node
+    lazy: Vec<A::F>,
+}
+
+impl<A: Action> LazySegmentTree<A> {
+    /// Creates a tree of `n` identities
+    pub fn new(n: usize) -> Self {
+        vec![A::M::identity(); n].into()
+    }
+
+    /// Recomputes node `k` from its children
+    fn update(&mut self, k: usize) {
+        self.data[k] = A::M::op(&self.data[2 * k], &self.data[2 * k + 1]);
+    }
+
+    /// Applies `f` to every element below node `k`, deferring it for the children
+    fn apply_node(&mut self, k: usize, f: &A::F) {
+        self.data[k] = A::apply(f, &self.data[k]);
+        if k < self.size {
+            self.lazy[k] = A::compose(f, &self.lazy[k]);
+        }
+    }
+
+    /// Passes the deferred map of node `k` on to its children
+    fn push(&mut self, k: usize) {
+        let f = std::mem::replace(&mut self.lazy[k], A::identity_map());
+        self.apply_node(2 * k, &f);
+        self.apply_node(2 * k + 1, &f);
+    }
+
+    /// Pushes every deferred map above leaf `p` down to it
+    fn push_to_leaf(&mut self, p: usize) {
+        for i in (1..=self.log).rev() {
+            self.push(p >> i);
+        }
+    }
+
+    /// Pushes every deferred map above the boundaries of the leaves `l..r` down to them
+    fn push_to_range(&mut self, l: usize, r: usize) {
+        for i in (1..=self.log).rev() {
+            if (l >> i) << i != l {
+                self.push(l >> i);
+            }
+            if (r >> i) << i != r {
+                self.push((r - 1) >> i);
+            }
+        }
+    }
+
+    /// Number of elements
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    /// Whether there are no elements
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Sets element `p` to `x`
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(p < self.len())
+    )]
+    pub fn set(&mut self, p: usize, x: <A::M as Monoid>::S) {
+        let p = p + self.size;
+        self.push_to_leaf(p);
+        self.data[p] = x;
+        for i in 1..=self.log {
+            self.update(p >> i);
+        }
+    }
+
+    /// Returns element `p`
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(p < self.len())
+    )]
+    pub fn get(&mut self, p: usize) -> &<A::M as Monoid>::S {
+        let p = p + self.size;
+        self.push_to_leaf(p);
+        &self.data[p]
+    }
+
+    /// The product of the elements `l..r`, in order
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(l <= r && r <= self.len())
+    )]
+    pub fn prod(&mut self, l: usize, r: usize) -> <A::M as Monoid>::S {
+        if l == r {
+            return A::M::identity();
+        }
+        let (mut l, mut r) = (l + self.size, r + self.size);
+        self.push_to_range(l, r);
+        let mut left = A::M::identity();
+        let mut right = A::M::identity();
+        while l < r {
+            if l & 1 == 1 {
+                left = A::M::op(&left, &self.data[l]);
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                right = A::M::op(&self.data[r], &right);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+        A::M::op(&left, &right)
+    }
+
+    /// The product of all elements
+    ///
+    /// Time Complexity: `O(1)`
+    pub fn all_prod(&self) -> <A::M as Monoid>::S {
+        self.data[1].clone()
+    }
+
+    /// Applies `f` to element `p`
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(p < self.len())
+    )]
+    pub fn apply(&mut self, p: usize, f: &A::F) {
+        let p = p + self.size;
+        self.push_to_leaf(p);
+        self.data[p] = A::apply(f, &self.data[p]);
+        for i in 1..=self.log {
+            self.update(p >> i);
+        }
+    }
+
+    /// Applies `f` to every element of `l..r`
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(l <= r && r <= self.len())
+    )]
+    pub fn apply_range(&mut self, l: usize, r: usize, f: &A::F) {
+        if l == r {
+            return;
+        }
+        let (l, r) = (l + self.size, r + self.size);
+        self.push_to_range(l, r);
+        let (mut a, mut b) = (l, r);
+        while a < b {
+            if a & 1 == 1 {
+                self.apply_node(a, f);
+                a += 1;
+            }
+            if b & 1 == 1 {
+                b -= 1;
+                self.apply_node(b, f);
+            }
+            a >>= 1;
+            b >>= 1;
+        }
+        for i in 1..=self.log {
+            if (l >> i) << i != l {
+                self.update(l >> i);
+            }
+            if (r >> i) << i != r {
+                self.update((r - 1) >> i);
+            }
+        }
+    }
+
+    /// The greatest `r` such that `pred(prod(l, r))` holds, given that `pred` holds for the
+    /// identity and is monotone, as for [SegmentTree::max_right]
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(l <= self.len()),
+        contracts::requires(pred(&A::M::identity())),
+        contracts::ensures(l <= ret && ret <= self.len()),
+        contracts::ensures(pred(&self.prod(l, ret))),
+        contracts::ensures(ret == self.len() || !pred(&self.prod(l, ret + 1)))
+    )]
+    pub fn max_right<P: Fn(&<A::M as Monoid>::S) -> bool>(&mut self, l: usize, pred: P) -> usize {
+        if l == self.n {
+            return self.n;
+        }
+        let mut l = l + self.size;
+        self.push_to_leaf(l);
+        let mut acc = A::M::identity();
+        loop {
+            while l.is_multiple_of(2) {
+                l >>= 1;
+            }
+            let next = A::M::op(&acc, &self.data[l]);
+            if !pred(&next) {
+                while l < self.size {
+                    self.push(l);
+                    l *= 2;
+                    let next = A::M::op(&acc, &self.data[l]);
+                    if pred(&next) {
+                        acc = next;
+                        l += 1;
+                    }
+                }
+                return l - self.size;
+            }
+            acc = next;
+            l += 1;
+            if l.is_power_of_two() {
+                return self.n;
+            }
+        }
+    }
+
+    /// The least `l` such that `pred(prod(l, r))` holds, given that `pred` holds for the
+    /// identity and is monotone, as for [SegmentTree::min_left]
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(r <= self.len()),
+        contracts::requires(pred(&A::M::identity())),
+        contracts::ensures(ret <= r),
+        contracts::ensures(pred(&self.prod(ret, r))),
+        contracts::ensures(ret == 0 || !pred(&self.prod(ret - 1, r)))
+    )]
+    pub fn min_left<P: Fn(&<A::M as Monoid>::S) -> bool>(&mut self, r: usize, pred: P) -> usize {
+        if r == 0 {
+            return 0;
+        }
+        let mut r = r + self.size;
+        self.push_to_leaf(r - 1);
+        let mut acc = A::M::identity();
+        loop {
+            r -= 1;
+            while r > 1 && !r.is_multiple_of(2) {
+                r >>= 1;
+            }
+            let next = A::M::op(&self.data[r], &acc);
+            if !pred(&next) {
+                while r < self.size {
+                    self.push(r);
+                    r = 2 * r + 1;
+                    let next = A::M::op(&self.data[r], &acc);
+                    if pred(&next) {
+                        acc = next;
+                        r -= 1;
+                    }
+                }
+                return r + 1 - self.size;
+            }
+            acc = next;
+            if r.is_power_of_two() {
+                return 0;
+            }
+        }
+    }
+}
+
+impl<A: Action> From<Vec<<A::M as Monoid>::S>> for LazySegmentTree<A> {
+    /// Builds the tree over the given elements
+    ///
+    /// Time Complexity: `O(n)`
+    fn from(values: Vec<<A::M as Monoid>::S>) -> Self {
+        let n = values.len();
+        let (size, log) = shape(n);
+        let mut data = vec![A::M::identity(); 2 * size];
+        data[size..size + n].clone_from_slice(&values);
+        let mut tree = LazySegmentTree {
+            n,
+            size,
+            log,
+            data,
+            lazy: vec![A::identity_map(); size],
+        };
+        for k in (1..size).rev() {
+            tree.update(k);
+        }
+        tree
+    }
+}
+
+impl<A: Action> Clone for LazySegmentTree<A> {
+    fn clone(&self) -> Self {
+        LazySegmentTree {
+            n: self.n,
+            size: self.size,
+            log: self.log,
+            data: self.data.clone(),
+            lazy: self.lazy.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{ENABLED, Kind, catch};
+    use crate::number_theory::field::Fp;
+    use crate::sorting::prop_test::Rng;
+
+    type F = Fp<998_244_353>;
+
+    /// Adding a constant to every element of a range, acting on its minimum
+    struct AddMin;
+
+    impl Action for AddMin {
+        type M = Minimum<i64>;
+        type F = i64;
+
+        fn identity_map() -> i64 {
+            0
+        }
+
+        fn compose(f: &i64, g: &i64) -> i64 {
+            f + g
+        }
+
+        fn apply(f: &i64, x: &i64) -> i64 {
+            // The identity stays the identity
+            if *x == i64::MAX { *x } else { x + f }
+        }
+    }
+
+    /// `(sum, count)` of a range, for sums under affine maps
+    struct SumCount;
+
+    impl Monoid for SumCount {
+        type S = (F, F);
+
+        fn identity() -> (F, F) {
+            (F::new(0), F::new(0))
+        }
+
+        fn op(a: &(F, F), b: &(F, F)) -> (F, F) {
+            (a.0 + b.0, a.1 + b.1)
+        }
+    }
+
+    /// Affine maps acting on every element of a range, as its sum changes
+    struct AffineSum;
+
+    impl Action for AffineSum {
+        type M = SumCount;
+        type F = AffineMap<F>;
+
+        fn identity_map() -> AffineMap<F> {
+            Affine::<F>::identity()
+        }
+
+        fn compose(f: &AffineMap<F>, g: &AffineMap<F>) -> AffineMap<F> {
+            Affine::op(g, f)
+        }
+
+        fn apply(f: &AffineMap<F>, x: &(F, F)) -> (F, F) {
+            (f.a * x.0 + f.b * x.1, x.1)
+        }
+    }
+
+    /// A random `l..r` with `r <= n`
+    fn range(rng: &mut Rng, n: usize) -> (usize, usize) {
+        let l = rng.below(n as u64 + 1) as usize;
+        (l, l + rng.below((n - l) as u64 + 1) as usize)
+    }
+
+    #[test]
+    fn point_updates_agree_with_model() {
+        let n = 37;
+        let mut rng = Rng::new(40);
+        let mut model: Vec<i64> = (0..n).map(|_| rng.symmetric(100) as i64).collect();
+        let mut sums = SegmentTree::<Sum<i64>>::from(model.clone());
+        let mut mins = SegmentTree::<Minimum<i64>>::from(model.clone());
+        let mut maxs = SegmentTree::<Maximum<i64>>::from(model.clone());
+        for _ in 0..300 {
+            let i = rng.below(n as u64) as usize;
+            let x = rng.symmetric(100) as i64;
+            model[i] = x;
+            sums.set(i, x);
+            mins.set(i, x);
+            maxs.set(i, x);
+
+            let (l, r) = range(&mut rng, n);
+            let slice = &model[l..r];
+            assert_eq!(sums.prod(l, r), slice.iter().sum::<i64>());
+            assert_eq!(
+                mins.prod(l, r),
+                slice.iter().copied().min().unwrap_or(i64::MAX)
+            );
+            assert_eq!(
+                maxs.prod(l, r),
+                slice.iter().copied().max().unwrap_or(i64::MIN)
+            );
+        }
+        assert_eq!(sums.all_prod(), model.iter().sum::<i64>());
+        assert_eq!(format!("{mins:?}"), format!("{model:?}"));
+    }
+
+    #[test]
+    fn affine_maps_compose_in_order() {
+        let maps: Vec<AffineMap<F>> = (1..=10)
+            .map(|i| AffineMap {
+                a: F::new(i),
+                b: F::new(i * i),
+            })
+            .collect();
+        let tree = SegmentTree::<Affine<F>>::from(maps.clone());
+        let x = F::new(12345);
+        for l in 0..=10 {
+            for r in l..=10 {
+                let expected = maps[l..r].iter().fold(x, |y, f| f.apply(y));
+                assert_eq!(tree.prod(l, r).apply(x), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn binary_searches_agree_with_brute_force() {
+        let n = 30;
+        let mut rng = Rng::new(0x5E6);
+        let values: Vec<i64> = (0..n).map(|_| rng.below(10) as i64).collect();
+        let tree = SegmentTree::<Sum<i64>>::from(values.clone());
+        for limit in [0, 5, 17, 40, 1000] {
+            let pred = |s: &i64| *s <= limit;
+            for l in 0..=n {
+                let expected = (l..=n)
+                    .take_while(|&r| pred(&values[l..r].iter().sum()))
+                    .last()
+                    .unwrap();
+                assert_eq!(tree.max_right(l, pred), expected);
+            }
+            for r in 0..=n {
+                let expected = (0..=r)
+                    .rev()
+                    .take_while(|&l| pred(&values[l..r].iter().sum()))
+                    .last()
+                    .unwrap();
+                assert_eq!(tree.min_left(r, pred), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn range_add_range_min_agrees_with_model() {
+        let n = 45;
+        let mut rng = Rng::new(0x1A2);
+        let mut model = vec![0i64; n];
+        let mut tree = LazySegmentTree::<AddMin>::from(model.clone());
+        for _ in 0..400 {
+            let (l, r) = range(&mut rng, n);
+            match rng.below(4) {
+                0 => {
+                    let x = rng.symmetric(50) as i64;
+                    tree.apply_range(l, r, &x);
+                    model[l..r].iter_mut().for_each(|y| *y += x);
+                }
+                1 if l < n => {
+                    let x = rng.symmetric(50) as i64;
+                    tree.set(l, x);
+                    model[l] = x;
+                }
+                2 if l < n => {
+                    assert_eq!(*tree.get(l), model[l]);
+                }
+                _ => {
+                    let expected = model[l..r].iter().copied().min().unwrap_or(i64::MAX);
+                    assert_eq!(tree.prod(l, r), expected);
+                    let limit = rng.symmetric(100) as i64;
+                    let above = |m: &i64| *m >= limit;
+                    let right = (l..n).find(|&i| model[i] < limit).unwrap_or(n);
+                    assert_eq!(tree.max_right(l, above), right);
+                    let left = (0..r)
+                        .rev()
+                        .find(|&i| model[i] < limit)
+                        .map_or(0, |i| i + 1);
+                    assert_eq!(tree.min_left(r, above), left);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn range_affine_range_sum() {
+        let n = 20;
+        let mut rng = Rng::new(0xAFF);
+        let mut model: Vec<F> = (0..n).map(|i| F::new(i as u64)).collect();
+        let mut tree = LazySegmentTree::<AffineSum>::from(
+            model.iter().map(|&x| (x, F::new(1))).collect::<Vec<_>>(),
+        );
+        for _ in 0..200 {
+            let (l, r) = range(&mut rng, n);
+            if rng.below(2) == 0 {
+                let f = AffineMap {
+                    a: F::new(rng.next_u64()),
+                    b: F::new(rng.next_u64()),
+                };
+                tree.apply_range(l, r, &f);
+                model[l..r].iter_mut().for_each(|x| *x = f.apply(*x));
+            } else {
+                let expected = model[l..r].iter().fold(F::new(0), |s, &x| s + x);
+                assert_eq!(tree.prod(l, r), (expected, F::new((r - l) as u64)));
+            }
+        }
+    }
+
+    #[test]
+    fn false_on_identity_violates_precondition() {
+        let tree = SegmentTree::<Sum<i64>>::from(vec![1, 2, 3]);
+        let result = catch(move || tree.max_right(0, |&s| s > 0));
+        assert_eq!(result.is_err(), ENABLED);
+        if let Err(v) = result {
+            assert_eq!(v.kind, Kind::Precondition);
+        }
+    }
+}
@@ -0,0 +1,541 @@
+/*!
+Data Structures
+
+Union-Find (Disjoint-Set Union)
+
+Implements:
+- [UnionFind], with union by rank and path compression
+- [RollbackUnionFind], with union by size and undo, for offline algorithms
+
+Both partition `0..n` into disjoint sets, each represented by a tree of parent pointers whose
+root names the set. The shape of the forest is a struct invariant, checked before and after
+every method call in debug mode, which makes each call `O(n)` there.
+*/
+
+#[cfg(any(debug_assertions, feature = "checked"))]
+use contracts::invariant;
+use std::fmt;
+
+/// The root and depth of every element of the forest `parent`, where roots are their own
+/// parent, or `None` if a parent is out of bounds or the pointers form a cycle.
+#[cfg(any(debug_assertions, feature = "checked", test))]
+fn roots_and_depths(parent: &[usize]) -> Option<Vec<(usize, usize)>> {
+    let n = parent.len();
+    let mut known: Vec<Option<(usize, usize)>> = vec![None; n];
+    let mut path = Vec::new();
+    for i in 0..n {
+        // Follow the pointers up to a root or an element already walked
+        let mut x = i;
+        while known[x].is_none() && parent[x] != x {
+            if path.len() == n || parent[x] >= n {
+                return None;
+            }
+            path.push(x);
+            x = parent[x];
+        }
+        let (root, mut depth) = *known[x].get_or_insert((x, 0));
+        while let Some(y) = path.pop() {
+            depth += 1;
+            known[y] = Some((root, depth));
+        }
+    }
+    known.into_iter().collect()
+}
+
+/**
+Union-Find
+
+A partition of `0..n` into disjoint sets that can be merged, such as the components of a
+graph as its edges are added in Kruskal's algorithm.
+
+Find Time Complexity: `O(α(n))` amortized
+Union Time Complexity: `O(α(n))` amortized
+*/
+#[derive(Clone)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    /// An upper bound on the height of each tree, only meaningful at roots
+    rank: Vec<u32>,
+    /// The number of elements of each set, only meaningful at roots
+    size: Vec<usize>,
+    components: usize,
+}
+
+impl UnionFind {
+    /// Creates `n` singleton sets `{0}, {1}, ..., {n - 1}`
+    pub fn new(n: usize) -> Self {
+        UnionFind {
+            parent: (0..n).collect(),
+            rank: vec![0; n],
+            size: vec![1; n],
+            components: n,
+        }
+    }
+
+    /// Whether the parent pointers form a forest in which ranks strictly increase towards the
+    /// roots, every root of rank `r` has at least `2^r` elements (so ranks are at most
+    /// `log2 n`), and the sizes and number of components match the forest.
+    #[cfg(any(debug_assertions, feature = "checked"))]
+    fn is_valid(&self) -> bool {
+        let n = self.parent.len();
+        let Some(walk) = roots_and_depths(&self.parent) else {
+            return false;
+        };
+        let mut counts = vec![0; n];
+        for &(root, _) in &walk {
+            counts[root] += 1;
+        }
+        let mut roots = 0;
+        for (i, &p) in self.parent.iter().enumerate() {
+            if p != i {
+                if self.rank[i] >= self.rank[p] {
+                    return false;
+                }
+                continue;
+            }
+            roots += 1;
+            let bounded = 1usize
+                .checked_shl(self.rank[i])
+                .is_some_and(|m| m <= self.size[i]);
+            if self.size[i] != counts[i] || !bounded {
+                return false;
+            }
+        }
+        self.rank.len() == n && self.size.len() == n && roots == self.components
+    }
+}
+
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::invariant(self.is_valid())
+)]
+impl UnionFind {
+    /// Number of elements
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    /// Whether there are no elements
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Number of disjoint sets
+    pub fn components(&self) -> usize {
+        self.components
+    }
+
+    /// Returns the representative of the set containing `x`, pointing every element on the
+    /// way directly at it
+    ///
+    /// Time Complexity: `O(α(n))` amortized
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(x < self.len()),
+        contracts::ensures(self.parent[ret] == ret)
+    )]
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut y = x;
+        while self.parent[y] != root {
+            y = std::mem::replace(&mut self.parent[y], root);
+        }
+        root
+    }
+
+    /// Whether `a` and `b` are in the same set
+    ///
+    /// Time Complexity: `O(α(n))` amortized
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(a < self.len() && b < self.len())
+    )]
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Number of elements in the set containing `x`
+    ///
+    /// Time Complexity: `O(α(n))` amortized
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(x < self.len()),
+        contracts::ensures(ret >= 1)
+    )]
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// Merges the sets containing `a` and `b`, returning whether they were different
+    ///
+    /// Time Complexity: `O(α(n))` amortized
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(a < self.len() && b < self.len()),
+        contracts::ensures(self.components() + ret as usize == old(self.components())),
+        contracts::ensures(self.same(a, b))
+    )]
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        // Hang the lower tree below the higher one
+        if self.rank[a] < self.rank[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        if self.rank[a] == self.rank[b] {
+            self.rank[a] += 1;
+        }
+        self.components -= 1;
+        true
+    }
+}
+
+impl fmt::Debug for UnionFind {
+    /// Shows the parent of every element
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.parent).finish()
+    }
+}
+
+/**
+Rollback Union-Find
+
+A [UnionFind] whose unions can be undone in reverse order, as needed by offline algorithms
+that explore a recursion tree of unions, such as offline dynamic connectivity. Without path
+compression, `find` does not modify the forest; union by size keeps every tree at most
+`log2 n` deep.
+
+Find Time Complexity: `O(log n)`
+Union Time Complexity: `O(log n)`
+Undo Time Complexity: `O(1)`
+*/
+#[derive(Clone)]
+pub struct RollbackUnionFind {
+    parent: Vec<usize>,
+    /// The number of elements of each set, only meaningful at roots
+    size: Vec<usize>,
+    components: usize,
+    /// One entry per call to `union`: the root hung below another one, if any
+    history: Vec<Option<usize>>,
+}
+
+impl RollbackUnionFind {
+    /// Creates `n` singleton sets `{0}, {1}, ..., {n - 1}`
+    pub fn new(n: usize) -> Self {
+        RollbackUnionFind {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            components: n,
+            history: Vec::new(),
+        }
+    }
+
+    /// Whether the parent pointers form a forest in which no element is deeper than `log2`
+    /// of the size of its set, the sizes and number of components match the forest, and
+    /// the history records exactly the merges made.
+    #[cfg(any(debug_assertions, feature = "checked"))]
+    fn is_valid(&self) -> bool {
+        let n = self.parent.len();
+        let Some(walk) = roots_and_depths(&self.parent) else {
+            return false;
+        };
+        let mut counts = vec![0; n];
+        for &(root, _) in &walk {
+            counts[root] += 1;
+        }
+        let roots = (0..n).filter(|&i| self.parent[i] == i).count();
+        let merges = self.history.iter().flatten().count();
+        self.size.len() == n
+            && walk.iter().all(|&(root, depth)| {
+                self.size[root] == counts[root] && 1usize << depth <= self.size[root]
+            })
+            && roots == self.components
+            && merges + self.components == n
+    }
+}
+
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::invariant(self.is_valid())
+)]
+impl RollbackUnionFind {
+    /// Number of elements
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    /// Whether there are no elements
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Number of disjoint sets
+    pub fn components(&self) -> usize {
+        self.components
+    }
+
+    /// Returns the representative of the set containing `x`
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(x < self.len()),
+        contracts::ensures(self.parent[ret] == ret)
+    )]
+    pub fn find(&self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            x = self.parent[x];
+        }
+        x
+    }
+
+    /// Whether `a` and `b` are in the same set
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(a < self.len() && b < self.len())
+    )]
+    pub fn same(&self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Number of elements in the set containing `x`
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(x < self.len()),
+        contracts::ensures(ret >= 1)
+    )]
+    pub fn size(&self, x: usize) -> usize {
+        self.size[self.find(x)]
+    }
+
+    /// Merges the sets containing `a` and `b`, returning whether they were different.
+    /// Either way, the call can be undone with [undo](Self::undo).
+    ///
+    /// Time Complexity: `O(log n)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(a < self.len() && b < self.len()),
+        contracts::ensures(self.components() + ret as usize == old(self.components())),
+        contracts::ensures(self.checkpoint() == old(self.checkpoint()) + 1),
+        contracts::ensures(self.same(a, b))
+    )]
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            self.history.push(None);
+            return false;
+        }
+        // Hang the smaller tree below the larger one
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.components -= 1;
+        self.history.push(Some(b));
+        true
+    }
+
+    /// Number of calls to [union](Self::union) that can be undone, to be passed to
+    /// [rollback](Self::rollback) later
+    pub fn checkpoint(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Undoes the last call to [union](Self::union) not undone yet, returning `false` if
+    /// there is none
+    ///
+    /// Time Complexity: `O(1)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(self.checkpoint() == old(self.checkpoint()).saturating_sub(ret as usize))
+    )]
+    pub fn undo(&mut self) -> bool {
+        let Some(entry) = self.history.pop() else {
+            return false;
+        };
+        if let Some(b) = entry {
+            let a = self.parent[b];
+            self.parent[b] = b;
+            self.size[a] -= self.size[b];
+            self.components += 1;
+        }
+        true
+    }
+
+    /// Undoes every call to [union](Self::union) made since `checkpoint` was taken
+    ///
+    /// Time Complexity: `O(1)` per call undone
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(checkpoint <= self.checkpoint()),
+        contracts::ensures(self.checkpoint() == checkpoint)
+    )]
+    pub fn rollback(&mut self, checkpoint: usize) {
+        while self.history.len() > checkpoint {
+            self.undo();
+        }
+    }
+}
+
+impl fmt::Debug for RollbackUnionFind {
+    /// Shows the parent of every element
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.parent).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{ENABLED, Kind, catch};
+    use crate::sorting::prop_test::Rng;
+
+    /// The set of every element, as the least element in it
+    fn labels(model: &[Vec<usize>], n: usize) -> Vec<usize> {
+        let mut label = vec![0; n];
+        for set in model {
+            let least = *set.iter().min().unwrap();
+            for &x in set {
+                label[x] = least;
+            }
+        }
+        label
+    }
+
+    /// Merges the sets containing `a` and `b` in the model
+    fn model_union(model: &mut Vec<Vec<usize>>, a: usize, b: usize) -> bool {
+        let i = model.iter().position(|s| s.contains(&a)).unwrap();
+        let j = model.iter().position(|s| s.contains(&b)).unwrap();
+        if i == j {
+            return false;
+        }
+        let moved = model.swap_remove(i.max(j));
+        model[i.min(j)].extend(moved);
+        true
+    }
+
+    #[test]
+    fn kruskal() {
+        // (weight, from, to)
+        let mut edges = vec![(7, 0, 1), (9, 0, 2), (14, 0, 5), (10, 1, 2), (15, 1, 3)];
+        edges.extend([(11, 2, 3), (2, 2, 5), (6, 3, 4), (9, 4, 5)]);
+        edges.sort();
+
+        let mut sets = UnionFind::new(6);
+        let mut weight = 0;
+        for (w, u, v) in edges {
+            if sets.union(u, v) {
+                weight += w;
+            }
+        }
+        assert_eq!(weight, 33);
+        assert_eq!(sets.components(), 1);
+        assert_eq!(sets.size(4), 6);
+    }
+
+    #[test]
+    fn random_unions_agree_with_model() {
+        let n = 60;
+        let mut rng = Rng::new(38);
+        let mut sets = UnionFind::new(n);
+        let mut model: Vec<Vec<usize>> = (0..n).map(|i| vec![i]).collect();
+        for _ in 0..200 {
+            let a = rng.below(n as u64) as usize;
+            let b = rng.below(n as u64) as usize;
+            assert_eq!(sets.union(a, b), model_union(&mut model, a, b));
+            assert_eq!(sets.components(), model.len());
+            let label = labels(&model, n);
+            let c = rng.below(n as u64) as usize;
+            assert_eq!(sets.same(a, c), label[a] == label[c]);
+            assert_eq!(
+                sets.size(c),
+                label.iter().filter(|&&l| l == label[c]).count()
+            );
+        }
+    }
+
+    #[test]
+    fn rollback_restores_earlier_partitions() {
+        let n = 40;
+        let mut rng = Rng::new(0xD5);
+        let mut sets = RollbackUnionFind::new(n);
+        let mut model: Vec<Vec<usize>> = (0..n).map(|i| vec![i]).collect();
+        // (checkpoint, model at that checkpoint)
+        let mut saved = Vec::new();
+        for _ in 0..300 {
+            match rng.below(4) {
+                0 => {
+                    if let Some((checkpoint, earlier)) = saved.pop() {
+                        sets.rollback(checkpoint);
+                        model = earlier;
+                    }
+                }
+                1 => saved.push((sets.checkpoint(), model.clone())),
+                _ => {
+                    let a = rng.below(n as u64) as usize;
+                    let b = rng.below(n as u64) as usize;
+                    assert_eq!(sets.union(a, b), model_union(&mut model, a, b));
+                }
+            }
+            assert_eq!(sets.components(), model.len());
+            let label = labels(&model, n);
+            for (x, &least) in label.iter().enumerate() {
+                assert!(sets.same(x, least));
+                assert_eq!(sets.size(x), sets.size(least));
+            }
+        }
+        sets.rollback(0);
+        assert_eq!(sets.components(), n);
+        assert!(!sets.undo());
+    }
+
+    #[test]
+    fn undo_reverts_one_union() {
+        let mut sets = RollbackUnionFind::new(3);
+        assert!(sets.union(0, 1));
+        assert!(!sets.union(1, 0));
+        assert!(sets.undo());
+        assert!(sets.same(0, 1));
+        assert!(sets.undo());
+        assert!(!sets.same(0, 1));
+        assert_eq!(sets.size(1), 1);
+    }
+
+    #[test]
+    fn cycle_violates_invariant() {
+        let mut sets = UnionFind::new(3);
+        sets.parent[0] = 1;
+        sets.parent[1] = 0;
+        sets.components = 1;
+        let result = catch(move || sets.len());
+        assert_eq!(result.is_err(), ENABLED);
+        if let Err(v) = result {
+            assert_eq!(v.kind, Kind::Invariant);
+            assert_eq!(v.module, "safe_dsa::data_structures::union_find");
+        }
+    }
+
+    #[test]
+    fn roots_and_depths_cases() {
+        assert_eq!(
+            roots_and_depths(&[0, 0, 1, 3]),
+            Some(vec![(0, 0), (0, 1), (0, 2), (3, 0)])
+        );
+        assert_eq!(roots_and_depths(&[1, 2, 0]), None);
+        assert_eq!(roots_and_depths(&[0, 5]), None);
+        assert_eq!(roots_and_depths(&[]), Some(vec![]));
+    }
+}
@@ -1,2 +1,5 @@
+pub mod contract;
+pub mod data_structures;
 pub mod number_theory;
+pub mod searching;
 pub mod sorting;
@@ -0,0 +1,1211 @@
+/*!
+Number Theory
+
+Arbitrary-Precision Integers
+
+Defines:
+- struct [BigUint], a natural number of any size
+- struct [BigInt], an integer of any size, as a sign and a [BigUint] magnitude
+- enum [ParseBigIntError]
+
+Implements:
+- Schoolbook multiplication, and Karatsuba's above [KARATSUBA_THRESHOLD] limbs
+- Long division (Knuth's Algorithm D)
+- Euclid's gcd, modular exponentiation, and conversion to and from radix 2 to 36
+
+Numbers are little-endian vectors of `u32` limbs, so that a product of two limbs and a carry
+fits in a `u64`. The vectors never end in a zero limb, so zero is empty and every number has
+exactly one representation.
+*/
+
+use crate::contract::loop_invariant;
+#[cfg(any(debug_assertions, feature = "checked"))]
+use contracts::invariant;
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Rem, Shl, Shr, Sub};
+use std::str::FromStr;
+
+/// Operands with fewer limbs than this are multiplied by the schoolbook method
+pub const KARATSUBA_THRESHOLD: usize = 32;
+
+/// Drops the zero limbs at the end of `v`
+fn trim(v: &mut Vec<u32>) {
+    while v.last() == Some(&0) {
+        v.pop();
+    }
+}
+
+/// Compares two trimmed limb slices
+fn cmp_limbs(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+/// `a + b`, possibly ending in zero limbs
+fn add_limbs(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut out = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0u64;
+    for (i, &x) in long.iter().enumerate() {
+        let t = x as u64 + short.get(i).copied().unwrap_or(0) as u64 + carry;
+        out.push(t as u32);
+        carry = t >> 32;
+    }
+    out.push(carry as u32);
+    out
+}
+
+/// `a -= b`, for `a >= b` as numbers. `b` may be longer than `a` only by zero limbs.
+fn sub_assign_limbs(a: &mut [u32], b: &[u32]) {
+    let mut borrow = 0i64;
+    for (i, x) in a.iter_mut().enumerate() {
+        if i >= b.len() && borrow == 0 {
+            break;
+        }
+        let t = *x as i64 - b.get(i).copied().unwrap_or(0) as i64 - borrow;
+        *x = t as u32;
+        borrow = (t < 0) as i64;
+    }
+    // a >= b, so nothing is left to borrow
+    loop_invariant!(borrow == 0);
+}
+
+/// Adds `x` into `out` from limb `offset` on. The sum must fit in `out`.
+fn add_at(out: &mut [u32], x: &[u32], offset: usize) {
+    let mut carry = 0u64;
+    for (i, o) in out[offset..].iter_mut().enumerate() {
+        if i >= x.len() && carry == 0 {
+            break;
+        }
+        let t = *o as u64 + x.get(i).copied().unwrap_or(0) as u64 + carry;
+        *o = t as u32;
+        carry = t >> 32;
+    }
+    // The sum fits in out, so nothing is left to carry
+    loop_invariant!(carry == 0);
+}
+
+/// `a · b` with `a.len() + b.len()` limbs, by the schoolbook method
+///
+/// Time Complexity: `O(a.len() · b.len())`
+fn schoolbook(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut out = vec![0; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (o, &y) in out[i..].iter_mut().zip(b) {
+            // At most (2^32 - 1)^2 + 2 (2^32 - 1) = 2^64 - 1
+            let t = x as u64 * y as u64 + *o as u64 + carry;
+            *o = t as u32;
+            carry = t >> 32;
+        }
+        out[i + b.len()] = carry as u32;
+    }
+    out
+}
+
+/// `a · b` with `a.len() + b.len()` limbs
+///
+/// Time Complexity: `O(n^log2(3))` for `n` limbs each, by Karatsuba's method, and
+/// `O(n · m^(log2(3) - 1))` for `n` and `m <= n / 2` limbs
+fn mul_limbs(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    if short.len() < KARATSUBA_THRESHOLD {
+        return schoolbook(a, b);
+    }
+    if long.len() >= 2 * short.len() {
+        // Splitting at half the short operand would leave most of the work to an equally
+        // unbalanced product, so multiply by balanced slices of the long one instead
+        let mut out = vec![0; a.len() + b.len()];
+        for (i, slice) in long.chunks(short.len()).enumerate() {
+            let mut p = mul_limbs(slice, short);
+            trim(&mut p);
+            add_at(&mut out, &p, i * short.len());
+        }
+        return out;
+    }
+    // a = a1·B^m + a0 and b = b1·B^m + b0, with B = 2^32
+    let m = a.len().min(b.len()) / 2;
+    let (a0, a1) = a.split_at(m);
+    let (b0, b1) = b.split_at(m);
+    let mut z0 = mul_limbs(a0, b0);
+    let mut z2 = mul_limbs(a1, b1);
+    trim(&mut z0);
+    trim(&mut z2);
+    // a0·b1 + a1·b0 = (a0 + a1)(b0 + b1) - z0 - z2
+    let mut z1 = mul_limbs(&add_limbs(a0, a1), &add_limbs(b0, b1));
+    sub_assign_limbs(&mut z1, &z0);
+    sub_assign_limbs(&mut z1, &z2);
+    trim(&mut z1);
+
+    let mut out = vec![0; a.len() + b.len()];
+    add_at(&mut out, &z0, 0);
+    add_at(&mut out, &z1, m);
+    add_at(&mut out, &z2, 2 * m);
+    out
+}
+
+/// `v · m + a`, in place
+fn mul_small_add(v: &mut Vec<u32>, m: u32, a: u32) {
+    let mut carry = a as u64;
+    for x in v.iter_mut() {
+        let t = *x as u64 * m as u64 + carry;
+        *x = t as u32;
+        carry = t >> 32;
+    }
+    if carry > 0 {
+        v.push(carry as u32);
+    }
+}
+
+/// `(v / d, v % d)` for a single limb `d > 0`
+fn div_rem_small(v: &[u32], d: u32) -> (Vec<u32>, u32) {
+    let mut q = vec![0; v.len()];
+    let mut r = 0u64;
+    for (qi, &x) in q.iter_mut().zip(v).rev() {
+        let cur = r << 32 | x as u64;
+        *qi = (cur / d as u64) as u32;
+        r = cur % d as u64;
+    }
+    trim(&mut q);
+    (q, r as u32)
+}
+
+/// `a << s` for `s < 32`, with one more limb than `a`
+fn shl_bits(a: &[u32], s: u32) -> Vec<u32> {
+    let mut out = Vec::with_capacity(a.len() + 1);
+    let mut carry = 0;
+    for &x in a {
+        out.push(x << s | carry);
+        carry = if s == 0 { 0 } else { x >> (32 - s) };
+    }
+    out.push(carry);
+    out
+}
+
+/// `(u / v, u % v)` for trimmed `u >= v` and `v` of at least 2 limbs, by Knuth's Algorithm D
+///
+/// Time Complexity: `O((u.len() - v.len() + 1) · v.len())`
+fn div_rem_knuth(u: &[u32], v: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    const B: u64 = 1 << 32;
+    let n = v.len();
+    let m = u.len() - n;
+    // Normalize so that the top limb of v has its high bit set, which makes each estimated
+    // quotient limb at most 2 too large
+    let s = v[n - 1].leading_zeros();
+    let vn = shl_bits(v, s);
+    let mut un = shl_bits(u, s);
+    let mut q = vec![0; m + 1];
+
+    for j in (0..=m).rev() {
+        let top = (un[j + n] as u64) << 32 | un[j + n - 1] as u64;
+        let mut qhat = top / vn[n - 1] as u64;
+        let mut rhat = top % vn[n - 1] as u64;
+        while qhat >= B || qhat * vn[n - 2] as u64 > (rhat << 32 | un[j + n - 2] as u64) {
+            qhat -= 1;
+            rhat += vn[n - 1] as u64;
+            if rhat >= B {
+                break;
+            }
+        }
+
+        // un[j..=j+n] -= qhat · vn
+        let mut borrow = 0i64;
+        for (x, &y) in un[j..j + n].iter_mut().zip(&vn[..n]) {
+            let p = qhat * y as u64;
+            let t = *x as i64 - borrow - (p & 0xffff_ffff) as i64;
+            *x = t as u32;
+            borrow = (p >> 32) as i64 - (t >> 32);
+        }
+        let t = un[j + n] as i64 - borrow;
+        un[j + n] = t as u32;
+
+        if t < 0 {
+            // qhat was one too large: add vn back
+            qhat -= 1;
+            let mut carry = 0u64;
+            for (x, &y) in un[j..j + n].iter_mut().zip(&vn[..n]) {
+                let t = *x as u64 + y as u64 + carry;
+                *x = t as u32;
+                carry = t >> 32;
+            }
+            un[j + n] = un[j + n].wrapping_add(carry as u32);
+        }
+        q[j] = qhat as u32;
+    }
+
+    // The remainder is the low n limbs of un, shifted back
+    let mut r: Vec<u32> = (0..n)
+        .map(|i| {
+            if s == 0 {
+                un[i]
+            } else {
+                un[i] >> s | un[i + 1] << (32 - s)
+            }
+        })
+        .collect();
+    trim(&mut q);
+    trim(&mut r);
+    (q, r)
+}
+
+/// The value of an ASCII digit in `radix`
+fn digit_value(c: u8, radix: u32) -> Option<u32> {
+    (c as char).to_digit(radix)
+}
+
+/// Panics unless `radix` is from 2 to 36, in every build, as [u32::from_str_radix] does
+fn check_radix(radix: u32) {
+    assert!(
+        (2..=36).contains(&radix),
+        "radix must lie in the range 2..=36, not {radix}"
+    );
+}
+
+/// The largest `k` and `radix^k` with `radix^k <= u32::MAX`
+fn radix_chunk(radix: u32) -> (usize, u32) {
+    let (mut k, mut power) = (0, 1u32);
+    while let Some(next) = power.checked_mul(radix) {
+        power = next;
+        k += 1;
+    }
+    (k, power)
+}
+
+/// Why a string is not a [BigUint] or [BigInt]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParseBigIntError {
+    /// No digits
+    Empty,
+    /// A character that is not a digit in the radix
+    InvalidDigit,
+}
+
+impl fmt::Display for ParseBigIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseBigIntError::Empty => write!(f, "cannot parse integer from empty string"),
+            ParseBigIntError::InvalidDigit => write!(f, "invalid digit found in string"),
+        }
+    }
+}
+
+impl Error for ParseBigIntError {}
+
+/**
+A natural number of any size
+
+Arithmetic is implemented on references, `&a + &b`, and also on values. Subtraction panics
+when the result would be negative, and division and remainder panic on zero divisors; see
+[checked_sub](BigUint::checked_sub) and [checked_div_rem](BigUint::checked_div_rem).
+*/
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// Little-endian, without zero limbs at the end
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    /// Wraps `limbs`, trimming its zero limbs at the end
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(ret.is_normalized())
+    )]
+    fn from_limbs(mut limbs: Vec<u32>) -> Self {
+        trim(&mut limbs);
+        BigUint { limbs }
+    }
+
+    /// The invariant: no zero limb at the end
+    #[cfg(any(debug_assertions, feature = "checked"))]
+    fn is_normalized(&self) -> bool {
+        self.limbs.last() != Some(&0)
+    }
+
+    #[inline]
+    pub fn zero() -> Self {
+        BigUint { limbs: Vec::new() }
+    }
+
+    #[inline]
+    pub fn one() -> Self {
+        BigUint { limbs: vec![1] }
+    }
+
+    /// Parses digits in `radix`, from 2 to 36, in either case; panics for any other radix
+    ///
+    /// Time Complexity: `O(n^2)` for `n` digits
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseBigIntError> {
+        check_radix(radix);
+        let digits = s.strip_prefix('+').unwrap_or(s).as_bytes();
+        if digits.is_empty() {
+            return Err(ParseBigIntError::Empty);
+        }
+        let (k, _) = radix_chunk(radix);
+        let mut limbs = Vec::new();
+        for chunk in digits.chunks(k) {
+            let mut power = 1;
+            let mut value = 0;
+            for &c in chunk {
+                let d = digit_value(c, radix).ok_or(ParseBigIntError::InvalidDigit)?;
+                value = value * radix + d;
+                power *= radix;
+            }
+            mul_small_add(&mut limbs, power, value);
+        }
+        Ok(BigUint::from_limbs(limbs))
+    }
+}
+
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::invariant(self.is_normalized())
+)]
+impl BigUint {
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// The number of significant bits, 0 for zero
+    #[inline]
+    pub fn bits(&self) -> u64 {
+        match self.limbs.last() {
+            Some(top) => self.limbs.len() as u64 * 32 - top.leading_zeros() as u64,
+            None => 0,
+        }
+    }
+
+    /// Bit `i`, counting from the least significant
+    #[inline]
+    pub fn bit(&self, i: u64) -> bool {
+        let limb = (i / 32) as usize;
+        limb < self.limbs.len() && self.limbs[limb] >> (i % 32) & 1 == 1
+    }
+
+    /// The value as a `u64`, or `None` if it does not fit
+    pub fn to_u64(&self) -> Option<u64> {
+        self.to_u128().and_then(|x| u64::try_from(x).ok())
+    }
+
+    /// The value as a `u128`, or `None` if it does not fit
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        Some(
+            self.limbs
+                .iter()
+                .rev()
+                .fold(0u128, |acc, &x| acc << 32 | x as u128),
+        )
+    }
+
+    /// `self - rhs`, or `None` if `rhs > self`
+    pub fn checked_sub(&self, rhs: &BigUint) -> Option<BigUint> {
+        if cmp_limbs(&self.limbs, &rhs.limbs) == Ordering::Less {
+            return None;
+        }
+        let mut limbs = self.limbs.clone();
+        sub_assign_limbs(&mut limbs, &rhs.limbs);
+        Some(BigUint::from_limbs(limbs))
+    }
+
+    /// `(self / d, self % d)`, or `None` if `d` is zero
+    ///
+    /// Time Complexity: `O((n - m + 1) · m)` for `n` and `m` limbs
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(ret.as_ref().is_none_or(|(q, r)| r < d && &(q * d) + r == *self))
+    )]
+    pub fn checked_div_rem(&self, d: &BigUint) -> Option<(BigUint, BigUint)> {
+        if d.is_zero() {
+            return None;
+        }
+        if cmp_limbs(&self.limbs, &d.limbs) == Ordering::Less {
+            return Some((BigUint::zero(), self.clone()));
+        }
+        Some(if d.limbs.len() == 1 {
+            let (q, r) = div_rem_small(&self.limbs, d.limbs[0]);
+            (BigUint::from_limbs(q), BigUint::from(r))
+        } else {
+            let (q, r) = div_rem_knuth(&self.limbs, &d.limbs);
+            (BigUint::from_limbs(q), BigUint::from_limbs(r))
+        })
+    }
+
+    /// `(self / d, self % d)`; panics if `d` is zero
+    #[inline]
+    pub fn div_rem(&self, d: &BigUint) -> (BigUint, BigUint) {
+        self.checked_div_rem(d)
+            .expect("division by zero in BigUint")
+    }
+
+    /// `self^e`
+    ///
+    /// Time Complexity: `O(log e)` multiplications
+    pub fn pow(&self, mut e: u32) -> BigUint {
+        let mut base = self.clone();
+        let mut acc = BigUint::one();
+        while e > 0 {
+            if e & 1 == 1 {
+                acc = &acc * &base;
+            }
+            e >>= 1;
+            if e > 0 {
+                base = &base * &base;
+            }
+        }
+        acc
+    }
+
+    /// `self^e mod m`, for `m > 0`
+    ///
+    /// Time Complexity: `O(log e)` multiplications and divisions of numbers below `m^2`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(!m.is_zero()),
+        contracts::ensures(ret < *m)
+    )]
+    pub fn modpow(&self, e: &BigUint, m: &BigUint) -> BigUint {
+        let base = self % m;
+        let mut acc = &BigUint::one() % m;
+        for i in (0..e.bits()).rev() {
+            acc = &(&acc * &acc) % m;
+            if e.bit(i) {
+                acc = &(&acc * &base) % m;
+            }
+        }
+        acc
+    }
+
+    /// The greatest common divisor, with `gcd(0, 0) = 0`
+    ///
+    /// Time Complexity: `O(log min(a, b))` divisions, by Euclid's algorithm
+    pub fn gcd(&self, other: &BigUint) -> BigUint {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let r = &a % &b;
+            (a, b) = (b, r);
+        }
+        a
+    }
+
+    /// The digits in `radix`, from 2 to 36, in lowercase; panics for any other radix
+    ///
+    /// Time Complexity: `O(n^2)` for `n` limbs
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        check_radix(radix);
+        if self.is_zero() {
+            return "0".to_string();
+        }
+        // Peel off k digits at a time, least significant chunk first
+        let (k, power) = radix_chunk(radix);
+        let mut chunks = Vec::new();
+        let mut rest = self.limbs.clone();
+        while !rest.is_empty() {
+            let (q, r) = div_rem_small(&rest, power);
+            chunks.push(r);
+            rest = q;
+        }
+        let mut out = String::with_capacity(chunks.len() * k);
+        for (i, &chunk) in chunks.iter().rev().enumerate() {
+            let mut digits = Vec::with_capacity(k);
+            let mut x = chunk;
+            while x > 0 || (i > 0 && digits.len() < k) || digits.is_empty() {
+                digits.push(char::from_digit(x % radix, radix).unwrap());
+                x /= radix;
+            }
+            out.extend(digits.iter().rev());
+        }
+        out
+    }
+}
+
+impl From<u32> for BigUint {
+    fn from(x: u32) -> Self {
+        BigUint::from_limbs(vec![x])
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(x: u64) -> Self {
+        BigUint::from(x as u128)
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(x: u128) -> Self {
+        BigUint::from_limbs((0..4).map(|i| (x >> (32 * i)) as u32).collect())
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_limbs(&self.limbs, &other.limbs)
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "", &self.to_str_radix(10))
+    }
+}
+
+impl fmt::Debug for BigUint {
+    /// Shows the decimal value
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::LowerHex for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0x", &self.to_str_radix(16))
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = ParseBigIntError;
+
+    /// Parses decimal digits
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BigUint::from_str_radix(s, 10)
+    }
+}
+
+/// Implements `$Op` on the owned and mixed operand forms of `$T`, through `&$T $op &$T`
+macro_rules! forward_binop {
+    ($T:ident, $($Op:ident $op:ident),* $(,)?) => {$(
+        impl $Op<$T> for $T {
+            type Output = $T;
+            #[inline]
+            fn $op(self, rhs: $T) -> $T {
+                $Op::$op(&self, &rhs)
+            }
+        }
+        impl $Op<&$T> for $T {
+            type Output = $T;
+            #[inline]
+            fn $op(self, rhs: &$T) -> $T {
+                $Op::$op(&self, rhs)
+            }
+        }
+        impl $Op<$T> for &$T {
+            type Output = $T;
+            #[inline]
+            fn $op(self, rhs: $T) -> $T {
+                $Op::$op(self, &rhs)
+            }
+        }
+    )*};
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn add(self, rhs: &BigUint) -> BigUint {
+        BigUint::from_limbs(add_limbs(&self.limbs, &rhs.limbs))
+    }
+}
+
+impl Sub<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn sub(self, rhs: &BigUint) -> BigUint {
+        self.checked_sub(rhs)
+            .expect("subtraction underflow in BigUint")
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn mul(self, rhs: &BigUint) -> BigUint {
+        if self.is_zero() || rhs.is_zero() {
+            return BigUint::zero();
+        }
+        BigUint::from_limbs(mul_limbs(&self.limbs, &rhs.limbs))
+    }
+}
+
+impl Div<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn div(self, rhs: &BigUint) -> BigUint {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn rem(self, rhs: &BigUint) -> BigUint {
+        self.div_rem(rhs).1
+    }
+}
+
+forward_binop!(BigUint, Add add, Sub sub, Mul mul, Div div, Rem rem);
+
+impl Shl<u64> for &BigUint {
+    type Output = BigUint;
+    fn shl(self, n: u64) -> BigUint {
+        if self.is_zero() {
+            return BigUint::zero();
+        }
+        let mut limbs = vec![0; (n / 32) as usize];
+        limbs.extend(shl_bits(&self.limbs, (n % 32) as u32));
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Shr<u64> for &BigUint {
+    type Output = BigUint;
+    fn shr(self, n: u64) -> BigUint {
+        let skip = (n / 32) as usize;
+        if skip >= self.limbs.len() {
+            return BigUint::zero();
+        }
+        let s = (n % 32) as u32;
+        let high = &self.limbs[skip..];
+        let limbs = (0..high.len())
+            .map(|i| {
+                let next = high.get(i + 1).copied().unwrap_or(0);
+                if s == 0 {
+                    high[i]
+                } else {
+                    high[i] >> s | next << (32 - s)
+                }
+            })
+            .collect();
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Shl<u64> for BigUint {
+    type Output = BigUint;
+    #[inline]
+    fn shl(self, n: u64) -> BigUint {
+        &self << n
+    }
+}
+
+impl Shr<u64> for BigUint {
+    type Output = BigUint;
+    #[inline]
+    fn shr(self, n: u64) -> BigUint {
+        &self >> n
+    }
+}
+
+/**
+An integer of any size
+
+Division truncates toward zero and the remainder takes the sign of the dividend, as for the
+primitive integers.
+*/
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: BigUint,
+}
+
+impl BigInt {
+    /// `-magnitude` if `negative`, else `magnitude`. Zero is never negative.
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(ret.is_normalized())
+    )]
+    pub fn new(negative: bool, magnitude: BigUint) -> Self {
+        BigInt {
+            negative: negative && !magnitude.is_zero(),
+            magnitude,
+        }
+    }
+
+    /// The invariant: zero is not negative
+    #[cfg(any(debug_assertions, feature = "checked"))]
+    fn is_normalized(&self) -> bool {
+        !(self.negative && self.magnitude.is_zero())
+    }
+
+    #[inline]
+    pub fn zero() -> Self {
+        BigInt::default()
+    }
+
+    #[inline]
+    pub fn one() -> Self {
+        BigInt::from(BigUint::one())
+    }
+
+    /// Parses an optional sign and digits in `radix`, from 2 to 36; panics for any other radix
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseBigIntError> {
+        check_radix(radix);
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) if !rest.starts_with('+') => (true, rest),
+            Some(_) => return Err(ParseBigIntError::InvalidDigit),
+            None => (false, s),
+        };
+        Ok(BigInt::new(
+            negative,
+            BigUint::from_str_radix(digits, radix)?,
+        ))
+    }
+}
+
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::invariant(self.is_normalized())
+)]
+impl BigInt {
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+
+    #[inline]
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// -1, 0 or 1
+    #[inline]
+    pub fn signum(&self) -> i32 {
+        if self.negative {
+            -1
+        } else if self.is_zero() {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// The absolute value
+    #[inline]
+    pub fn magnitude(&self) -> &BigUint {
+        &self.magnitude
+    }
+
+    /// The value as an `i64`, or `None` if it does not fit
+    pub fn to_i64(&self) -> Option<i64> {
+        self.to_i128().and_then(|x| i64::try_from(x).ok())
+    }
+
+    /// The value as an `i128`, or `None` if it does not fit
+    pub fn to_i128(&self) -> Option<i128> {
+        let m = self.magnitude.to_u128()?;
+        if self.negative {
+            0i128.checked_sub_unsigned(m)
+        } else {
+            i128::try_from(m).ok()
+        }
+    }
+
+    /// `(self / d, self % d)`, truncating toward zero, or `None` if `d` is zero
+    pub fn checked_div_rem(&self, d: &BigInt) -> Option<(BigInt, BigInt)> {
+        let (q, r) = self.magnitude.checked_div_rem(&d.magnitude)?;
+        Some((
+            BigInt::new(self.negative != d.negative, q),
+            BigInt::new(self.negative, r),
+        ))
+    }
+
+    /// `(self / d, self % d)`, truncating toward zero; panics if `d` is zero
+    #[inline]
+    pub fn div_rem(&self, d: &BigInt) -> (BigInt, BigInt) {
+        self.checked_div_rem(d).expect("division by zero in BigInt")
+    }
+
+    /// `self^e`
+    pub fn pow(&self, e: u32) -> BigInt {
+        BigInt::new(self.negative && e % 2 == 1, self.magnitude.pow(e))
+    }
+
+    /// The greatest common divisor of the absolute values
+    #[inline]
+    pub fn gcd(&self, other: &BigInt) -> BigUint {
+        self.magnitude.gcd(&other.magnitude)
+    }
+
+    /// An optional `-` and the digits in `radix`, from 2 to 36, in lowercase; panics for any
+    /// other radix
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        let digits = self.magnitude.to_str_radix(radix);
+        if self.negative {
+            format!("-{digits}")
+        } else {
+            digits
+        }
+    }
+}
+
+impl From<BigUint> for BigInt {
+    fn from(magnitude: BigUint) -> Self {
+        BigInt::new(false, magnitude)
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(x: i64) -> Self {
+        BigInt::from(x as i128)
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(x: i128) -> Self {
+        BigInt::new(x < 0, BigUint::from(x.unsigned_abs()))
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.negative, "", &self.magnitude.to_str_radix(10))
+    }
+}
+
+impl fmt::Debug for BigInt {
+    /// Shows the decimal value
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    /// Parses an optional sign and decimal digits
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BigInt::from_str_radix(s, 10)
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+    #[inline]
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.magnitude)
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+    #[inline]
+    fn neg(self) -> BigInt {
+        -self.clone()
+    }
+}
+
+impl Add<&BigInt> for &BigInt {
+    type Output = BigInt;
+    fn add(self, rhs: &BigInt) -> BigInt {
+        if self.negative == rhs.negative {
+            return BigInt::new(self.negative, &self.magnitude + &rhs.magnitude);
+        }
+        // Opposite signs: the larger magnitude wins
+        match self.magnitude.cmp(&rhs.magnitude) {
+            Ordering::Less => BigInt::new(rhs.negative, &rhs.magnitude - &self.magnitude),
+            _ => BigInt::new(self.negative, &self.magnitude - &rhs.magnitude),
+        }
+    }
+}
+
+impl Sub<&BigInt> for &BigInt {
+    type Output = BigInt;
+    fn sub(self, rhs: &BigInt) -> BigInt {
+        self + &-rhs
+    }
+}
+
+impl Mul<&BigInt> for &BigInt {
+    type Output = BigInt;
+    fn mul(self, rhs: &BigInt) -> BigInt {
+        BigInt::new(
+            self.negative != rhs.negative,
+            &self.magnitude * &rhs.magnitude,
+        )
+    }
+}
+
+impl Div<&BigInt> for &BigInt {
+    type Output = BigInt;
+    fn div(self, rhs: &BigInt) -> BigInt {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem<&BigInt> for &BigInt {
+    type Output = BigInt;
+    fn rem(self, rhs: &BigInt) -> BigInt {
+        self.div_rem(rhs).1
+    }
+}
+
+forward_binop!(BigInt, Add add, Sub sub, Mul mul, Div div, Rem rem);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{ENABLED, Kind, catch};
+    use crate::number_theory::field::pow_mod;
+    use crate::sorting::prop_test::Rng;
+
+    /// A random number of exactly `limbs` limbs
+    fn random(rng: &mut Rng, limbs: usize) -> BigUint {
+        let mut v: Vec<u32> = (0..limbs).map(|_| rng.next_u64() as u32).collect();
+        if let Some(top) = v.last_mut() {
+            *top |= 1;
+        }
+        BigUint::from_limbs(v)
+    }
+
+    #[test]
+    fn agrees_with_u128() {
+        let mut rng = Rng::new(49);
+        for _ in 0..2000 {
+            // Mix widths so that every limb count from 0 to 4 shows up
+            let x = (rng.next_u64() as u128 * rng.next_u64() as u128) >> rng.below(128);
+            let y = (rng.next_u64() as u128) << 64 | rng.next_u64() as u128;
+            let y = y >> rng.below(128);
+            let (a, b) = (BigUint::from(x), BigUint::from(y));
+            assert_eq!(a.cmp(&b), x.cmp(&y));
+            assert_eq!((&a + &b).to_u128(), x.checked_add(y));
+            assert_eq!(
+                a.checked_sub(&b).and_then(|d| d.to_u128()),
+                x.checked_sub(y)
+            );
+            if let Some(p) = x.checked_mul(y) {
+                assert_eq!((&a * &b).to_u128(), Some(p));
+            }
+            if let Some(q) = x.checked_div(y) {
+                let (qa, ra) = a.div_rem(&b);
+                assert_eq!((qa.to_u128(), ra.to_u128()), (Some(q), Some(x % y)));
+            }
+            assert_eq!(a.bits(), 128 - x.leading_zeros() as u64);
+            let shift = rng.below(40);
+            assert_eq!((&a >> shift).to_u128(), Some(x >> shift));
+            assert_eq!(&(&a << shift) >> shift, a);
+        }
+    }
+
+    #[test]
+    fn long_division_corner_cases() {
+        let cases: [(u128, u128); 6] = [
+            // Hacker's Delight: the estimated quotient limb needs the add-back step
+            (
+                0x8000_0000_0000_0000_0000_0003,
+                0x2000_0000_0000_0000_0000_0001,
+            ),
+            // and the multiply-subtract overflows a signed limb
+            (
+                0x7fff_ffff_8000_0000_0000_0000_0000_0000,
+                0x8000_0000_0000_0000_0000_0001,
+            ),
+            (
+                0x0000_8000_0000_fffe_0000_0000_0000_0000,
+                0x8000_0000_ffff_ffff,
+            ),
+            (u128::MAX, (1 << 64) - 1),
+            (u128::MAX, 1 << 64),
+            (u128::MAX - 1, u128::MAX),
+        ];
+        for (x, y) in cases {
+            let (q, r) = BigUint::from(x).div_rem(&BigUint::from(y));
+            assert_eq!((q.to_u128(), r.to_u128()), (Some(x / y), Some(x % y)));
+        }
+
+        let mut rng = Rng::new(0x49);
+        for (n, m) in [(40, 3), (100, 60), (64, 64), (200, 2), (7, 5)] {
+            let (a, d) = (random(&mut rng, n), random(&mut rng, m));
+            let (q, r) = a.div_rem(&d);
+            assert!(r < d);
+            assert_eq!(&(&q * &d) + &r, a);
+            // Exact division
+            assert_eq!((&a * &d).div_rem(&d), (a.clone(), BigUint::zero()));
+        }
+        assert_eq!(BigUint::one().checked_div_rem(&BigUint::zero()), None);
+    }
+
+    #[test]
+    fn karatsuba_agrees_with_schoolbook() {
+        let mut rng = Rng::new(4949);
+        let sizes = [
+            (32, 32),
+            (33, 100),
+            (150, 150),
+            (500, 40),
+            (40, 500),
+            (1000, 33),
+            (70, 1001),
+            (257, 255),
+        ];
+        for (n, m) in sizes {
+            let (a, b) = (random(&mut rng, n), random(&mut rng, m));
+            let mut expected = schoolbook(&a.limbs, &b.limbs);
+            trim(&mut expected);
+            assert_eq!((&a * &b).limbs, expected, "{n} x {m}");
+        }
+        // All-ones limbs produce the largest carries
+        let ones = BigUint::from_limbs(vec![u32::MAX; 300]);
+        let square = &ones * &ones;
+        // (2^9600 - 1)^2 = 2^19200 - 2^9601 + 1
+        let expected = &(&BigUint::one() << 19200) - &(&BigUint::one() << 9601);
+        assert_eq!(square, expected + BigUint::one());
+    }
+
+    #[test]
+    fn radix_conversion() {
+        let factorial = (1..=30u32).fold(BigUint::one(), |acc, i| acc * BigUint::from(i));
+        assert_eq!(factorial.to_string(), "265252859812191058636308480000000");
+        let two_128 = &BigUint::one() << 128;
+        assert_eq!(
+            two_128.to_string(),
+            "340282366920938463463374607431768211456"
+        );
+        assert_eq!(format!("{two_128:#x}"), format!("0x1{}", "0".repeat(32)));
+        assert_eq!(BigUint::from(255u32).to_str_radix(2), "11111111");
+        assert_eq!(BigUint::from(1_000_000u32).to_str_radix(36), "lfls");
+        assert_eq!(BigUint::zero().to_string(), "0");
+        assert_eq!(format!("{:>5}", BigUint::from(42u32)), "   42");
+
+        let mut rng = Rng::new(7);
+        for radix in [2, 3, 7, 10, 16, 36] {
+            let x = random(&mut rng, 20);
+            let s = x.to_str_radix(radix);
+            assert_eq!(BigUint::from_str_radix(&s, radix), Ok(x.clone()));
+            assert_eq!(
+                BigUint::from_str_radix(&s.to_uppercase(), radix),
+                Ok(x.clone())
+            );
+        }
+        assert_eq!("000123".parse(), Ok(BigUint::from(123u32)));
+        assert_eq!("".parse::<BigUint>(), Err(ParseBigIntError::Empty));
+        assert_eq!("+".parse::<BigUint>(), Err(ParseBigIntError::Empty));
+        assert_eq!(
+            "12a".parse::<BigUint>(),
+            Err(ParseBigIntError::InvalidDigit)
+        );
+        assert_eq!("-1".parse::<BigUint>(), Err(ParseBigIntError::InvalidDigit));
+        assert_eq!(
+            BigUint::from_str_radix("2", 2),
+            Err(ParseBigIntError::InvalidDigit)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "radix must lie in the range 2..=36, not 1")]
+    fn from_str_radix_rejects_radix_one() {
+        // Would loop forever looking for the number of digits per limb
+        let _ = BigUint::from_str_radix("0", 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "radix must lie in the range 2..=36, not 37")]
+    fn to_str_radix_rejects_radix_above_36() {
+        let _ = BigInt::from(-5i64).to_str_radix(37);
+    }
+
+    #[test]
+    fn gcd_and_modpow() {
+        let m127 = &(&BigUint::one() << 127) - &BigUint::one();
+        let three = BigUint::from(3u32);
+        // Fermat's little theorem for the prime 2^127 - 1
+        assert_eq!(
+            three.modpow(&(&m127 - &BigUint::one()), &m127),
+            BigUint::one()
+        );
+        // Textbook RSA: n = 61 * 53, e = 17, d = 2753
+        let n = BigUint::from(3233u32);
+        let c = BigUint::from(65u32).modpow(&BigUint::from(17u32), &n);
+        assert_eq!(c, BigUint::from(2790u32));
+        assert_eq!(c.modpow(&BigUint::from(2753u32), &n), BigUint::from(65u32));
+        assert_eq!(
+            three.modpow(&BigUint::zero(), &BigUint::one()),
+            BigUint::zero()
+        );
+
+        let mut rng = Rng::new(11);
+        for _ in 0..100 {
+            let (b, e, m) = (rng.next_u64(), rng.next_u64(), rng.next_u64() | 1);
+            let got = BigUint::from(b).modpow(&BigUint::from(e), &BigUint::from(m));
+            assert_eq!(got.to_u64(), Some(pow_mod(b, e, m)));
+        }
+
+        let a = &three.pow(200) * &BigUint::from(1u64 << 40);
+        let b = &three.pow(150) * &BigUint::from(10u32).pow(30);
+        // 3^150 * 2^30: the 5^30 in b and the 2^10 left in a share nothing
+        assert_eq!(a.gcd(&b), &three.pow(150) * &(&BigUint::one() << 30));
+        assert_eq!(BigUint::zero().gcd(&b), b);
+        assert_eq!(BigUint::zero().gcd(&BigUint::zero()), BigUint::zero());
+    }
+
+    #[test]
+    fn signed_arithmetic_agrees_with_i128() {
+        let mut rng = Rng::new(490);
+        let mut random_i128 =
+            || (rng.next_u64() as i64 as i128) * (rng.below(1 << 20) as i128 - (1 << 19));
+        for _ in 0..1000 {
+            let (x, y) = (random_i128(), random_i128());
+            let (a, b) = (BigInt::from(x), BigInt::from(y));
+            assert_eq!(a.cmp(&b), x.cmp(&y));
+            assert_eq!((&a + &b).to_i128(), x.checked_add(y));
+            assert_eq!((&a - &b).to_i128(), x.checked_sub(y));
+            if let Some(p) = x.checked_mul(y) {
+                assert_eq!((&a * &b).to_i128(), Some(p));
+            }
+            if let Some(q) = x.checked_div(y) {
+                assert_eq!((&a / &b).to_i128(), Some(q));
+                assert_eq!((&a % &b).to_i128(), Some(x % y));
+            }
+            assert_eq!(a.signum(), x.signum() as i32);
+            assert_eq!(a.to_string(), x.to_string());
+            assert_eq!(a.to_string().parse(), Ok(a.clone()));
+        }
+
+        let min = BigInt::from(i128::MIN);
+        assert_eq!(min.to_i128(), Some(i128::MIN));
+        assert_eq!((-&min).to_i128(), None);
+        assert_eq!(BigInt::from(-5i64).pow(3), BigInt::from(-125i64));
+        assert_eq!(
+            BigInt::from(-6i64).gcd(&BigInt::from(4i64)),
+            BigUint::from(2u32)
+        );
+        assert_eq!(BigInt::from_str_radix("-ff", 16), Ok(BigInt::from(-255i64)));
+        assert_eq!("-0".parse(), Ok(BigInt::zero()));
+        assert!(!"-0".parse::<BigInt>().unwrap().is_negative());
+        assert_eq!("-+1".parse::<BigInt>(), Err(ParseBigIntError::InvalidDigit));
+        assert_eq!(format!("{:+}", BigInt::from(7i64)), "+7");
+        assert_eq!(BigInt::from(-3i64) - BigInt::from(-3i64), BigInt::zero());
+    }
+
+    #[test]
+    fn trailing_zero_limb_violates_invariant() {
+        let x = BigUint { limbs: vec![1, 0] };
+        let result = catch(move || x.bits());
+        assert_eq!(result.is_err(), ENABLED);
+        if let Err(v) = result {
+            assert_eq!(v.kind, Kind::Invariant);
+            assert_eq!(v.module, "safe_dsa::number_theory::big_int");
+        }
+    }
+}
@@ -0,0 +1,650 @@
+/*!
+Number Theory
+
+Extension Fields
+
+Defines:
+- trait [IrreducibleModulus], a marker type naming the polynomial that defines an [Fpk]
+- struct [SmallestIrreducible], which finds that polynomial at compile time
+- struct [Fpk], the field with `P^K` elements
+- const fns [is_irreducible] and [smallest_irreducible]
+
+Implements [Field] and [FiniteField] for:
+- [Fpk]
+
+An element of `GF(P^K)` is a polynomial of degree below `K` over [Fp]`<P>`, and arithmetic
+is modulo a monic irreducible polynomial `f = x^K + f[K-1]·x^(K-1) + ... + f[0]`, of which
+only the low coefficients `f[0..K]` are stored. The modulus is part of the type, so it is
+checked to be irreducible at compile time, by Rabin's test.
+*/
+
+use crate::number_theory::field::{
+    Field, FiniteField, Fp, add_mod, is_prime_u64, mul_mod, pow_mod, sub_mod,
+};
+#[cfg(any(debug_assertions, feature = "checked"))]
+use contracts::invariant;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// `a·x mod f`
+const fn mul_x<const P: u64, const K: usize>(a: [u64; K], f: &[u64; K]) -> [u64; K] {
+    // x^K = -(f[K-1]·x^(K-1) + ... + f[0])
+    let top = a[K - 1];
+    let mut out = [0; K];
+    let mut i = K - 1;
+    while i > 0 {
+        out[i] = sub_mod(a[i - 1], mul_mod(top, f[i], P), P);
+        i -= 1;
+    }
+    out[0] = sub_mod(0, mul_mod(top, f[0], P), P);
+    out
+}
+
+/// `a·b mod f`, by Horner's rule over the coefficients of `a`
+///
+/// Time Complexity: `O(K^2)`
+const fn mul_poly<const P: u64, const K: usize>(
+    a: &[u64; K],
+    b: &[u64; K],
+    f: &[u64; K],
+) -> [u64; K] {
+    let mut acc = [0; K];
+    let mut i = K;
+    while i > 0 {
+        i -= 1;
+        acc = mul_x::<P, K>(acc, f);
+        let mut j = 0;
+        while j < K {
+            acc[j] = add_mod(acc[j], mul_mod(a[i], b[j], P), P);
+            j += 1;
+        }
+    }
+    acc
+}
+
+/// `a^e mod f`
+///
+/// Time Complexity: `O(K^2 log e)`
+const fn pow_poly<const P: u64, const K: usize>(
+    a: &[u64; K],
+    mut e: u64,
+    f: &[u64; K],
+) -> [u64; K] {
+    let mut base = *a;
+    let mut acc = [0; K];
+    acc[0] = 1;
+    while e > 0 {
+        if e & 1 == 1 {
+            acc = mul_poly::<P, K>(&acc, &base, f);
+        }
+        base = mul_poly::<P, K>(&base, &base, f);
+        e >>= 1;
+    }
+    acc
+}
+
+/// Whether `h` is invertible modulo `f`, that is `gcd(h, f) = 1`: the matrix of
+/// multiplication by `h` is nonsingular
+///
+/// Time Complexity: `O(K^3)`, by Gaussian elimination
+const fn is_unit<const P: u64, const K: usize>(h: &[u64; K], f: &[u64; K]) -> bool {
+    // Row j is h·x^j
+    let mut m = [[0; K]; K];
+    let mut row = *h;
+    let mut j = 0;
+    while j < K {
+        m[j] = row;
+        row = mul_x::<P, K>(row, f);
+        j += 1;
+    }
+    let mut col = 0;
+    while col < K {
+        let mut pivot = col;
+        while pivot < K && m[pivot][col] == 0 {
+            pivot += 1;
+        }
+        if pivot == K {
+            return false;
+        }
+        let tmp = m[pivot];
+        m[pivot] = m[col];
+        m[col] = tmp;
+        let inv = pow_mod(m[col][col], P - 2, P);
+        let mut i = col + 1;
+        while i < K {
+            let factor = mul_mod(m[i][col], inv, P);
+            let mut c = col;
+            while c < K {
+                m[i][c] = sub_mod(m[i][c], mul_mod(factor, m[col][c], P), P);
+                c += 1;
+            }
+            i += 1;
+        }
+        col += 1;
+    }
+    true
+}
+
+/**
+Whether `x^K + f[K-1]·x^(K-1) + ... + f[0]` is irreducible over `GF(P)`, for a prime `P`,
+`K >= 1` and coefficients below `P`
+
+Rabin's test: it is irreducible exactly when `x^(P^K) = x` modulo it, and
+`gcd(x^(P^(K/q)) - x, f) = 1` for every prime `q` dividing `K`.
+
+Time Complexity: `O(K^3 log P)`
+*/
+pub const fn is_irreducible<const P: u64, const K: usize>(f: &[u64; K]) -> bool {
+    let mut x = [0; K];
+    if K == 1 {
+        x[0] = sub_mod(0, f[0], P);
+    } else {
+        x[1] = 1;
+    }
+    // y = x^(P^j) mod f
+    let mut y = x;
+    let mut j = 1;
+    while j <= K {
+        y = pow_poly::<P, K>(&y, P, f);
+        if j < K && K.is_multiple_of(j) && is_prime_u64((K / j) as u64) {
+            let mut h = y;
+            let mut i = 0;
+            while i < K {
+                h[i] = sub_mod(h[i], x[i], P);
+                i += 1;
+            }
+            if !is_unit::<P, K>(&h, f) {
+                return false;
+            }
+        }
+        j += 1;
+    }
+    let mut i = 0;
+    while i < K {
+        if y[i] != x[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// The low coefficients of the first monic irreducible polynomial of degree `K` over
+/// `GF(P)`, reading `f[0..K]` as the digits of a base-`P` number, `f[0]` highest, from
+/// `x^K + 1` on (`x` when `K = 1`). For example `x^2 + 1` when `P = 3 (mod 4)`.
+///
+/// Varying the high coefficients first finds one after about `K` tries, where varying the
+/// constant term first could try every `x^3 + c` for a large `P = 2 (mod 3)`, all reducible.
+pub const fn smallest_irreducible<const P: u64, const K: usize>() -> [u64; K] {
+    let mut f = [0; K];
+    // A constant term of zero is a factor of x
+    f[0] = if K == 1 { 0 } else { 1 };
+    // Irreducible polynomials exist in every degree, so this ends
+    while !is_irreducible::<P, K>(&f) {
+        let mut i = K - 1;
+        loop {
+            f[i] += 1;
+            if f[i] < P {
+                break;
+            }
+            f[i] = 0;
+            i -= 1;
+        }
+    }
+    f
+}
+
+/// A marker type naming the monic irreducible polynomial of degree `K` over `GF(P)` that
+/// defines an [Fpk], which is checked at compile time
+pub trait IrreducibleModulus<const P: u64, const K: usize> {
+    /// `f[0..K]`, for `f = x^K + f[K-1]·x^(K-1) + ... + f[0]`
+    const COEFFICIENTS: [u64; K];
+}
+
+/// The [smallest_irreducible] polynomial, found at compile time
+#[derive(Copy, Clone, Debug)]
+pub struct SmallestIrreducible;
+
+impl<const P: u64, const K: usize> IrreducibleModulus<P, K> for SmallestIrreducible {
+    const COEFFICIENTS: [u64; K] = smallest_irreducible::<P, K>();
+}
+
+/**
+The field with `P^K` elements, `GF(P^K)`, modulo the polynomial named by `M`
+
+By default the modulus is the [smallest_irreducible] one. Another one is named by a marker
+type, and rejected at compile time unless it is irreducible:
+
+```
+use safe_dsa::number_theory::extension_field::{Fpk, IrreducibleModulus};
+use safe_dsa::number_theory::field::Field;
+
+/// x^2 + 1, irreducible since 1_000_000_007 = 3 (mod 4)
+struct ISquaredPlusOne;
+impl IrreducibleModulus<1_000_000_007, 2> for ISquaredPlusOne {
+    const COEFFICIENTS: [u64; 2] = [1, 0];
+}
+
+type Gaussian = Fpk<1_000_000_007, 2, ISquaredPlusOne>;
+let i = Gaussian::x();
+assert_eq!(i * i, -Gaussian::one());
+```
+
+```compile_fail
+use safe_dsa::number_theory::extension_field::{Fpk, IrreducibleModulus};
+
+/// x^2 + 1 = (x + 2)(x + 3) over GF(5)
+struct Reducible;
+impl IrreducibleModulus<5, 2> for Reducible {
+    const COEFFICIENTS: [u64; 2] = [1, 0];
+}
+
+let x = Fpk::<5, 2, Reducible>::x();
+```
+
+The [FiniteField] methods need the order `P^K` to fit in a `u64`.
+*/
+pub struct Fpk<const P: u64, const K: usize, M = SmallestIrreducible>([u64; K], PhantomData<M>);
+
+impl<const P: u64, const K: usize, M: IrreducibleModulus<P, K>> Fpk<P, K, M> {
+    /// The low coefficients of the modulus, rejected at compile time unless it is
+    /// irreducible. Every operation goes through it.
+    pub const MODULUS: [u64; K] = {
+        assert!(K >= 1, "the degree K of Fpk<P, K> must be positive");
+        let _ = Fp::<P>::MODULUS;
+        let f = M::COEFFICIENTS;
+        let mut i = 0;
+        while i < K {
+            assert!(f[i] < P, "the coefficients of the modulus must be below P");
+            i += 1;
+        }
+        assert!(
+            is_irreducible::<P, K>(&f),
+            "the modulus of Fpk<P, K, M> must be irreducible"
+        );
+        f
+    };
+
+    /// `c[0] + c[1]·x + ... + c[K-1]·x^(K-1)`, with each coefficient reduced modulo `P`
+    #[inline]
+    pub fn new(c: [u64; K]) -> Self {
+        let _ = Self::MODULUS;
+        Fpk(c.map(|c| c % P), PhantomData)
+    }
+
+    /// The class of `x`, a root of the modulus
+    #[inline]
+    pub fn x() -> Self {
+        Fpk(mul_x::<P, K>(Self::one().0, &Self::MODULUS), PhantomData)
+    }
+}
+
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::invariant(self.0.iter().all(|&c| c < P))
+)]
+impl<const P: u64, const K: usize, M: IrreducibleModulus<P, K>> Fpk<P, K, M> {
+    /// The coefficients, constant term first
+    #[inline]
+    pub fn coefficients(self) -> [Fp<P>; K] {
+        self.0.map(Fp::<P>::new)
+    }
+
+    /// Whether `self` lies in the prime field `GF(P)`
+    #[inline]
+    pub fn is_constant(self) -> bool {
+        self.0[1..].iter().all(|&c| c == 0)
+    }
+}
+
+/// Drops the zero coefficients at the end of `v`
+fn trim(v: &mut Vec<u64>) {
+    while v.last() == Some(&0) {
+        v.pop();
+    }
+}
+
+/// `a·b` for polynomials over `GF(P)`
+fn poly_mul<const P: u64>(a: &[u64], b: &[u64]) -> Vec<u64> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut out = vec![0; a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (o, &y) in out[i..].iter_mut().zip(b) {
+            *o = add_mod(*o, mul_mod(x, y, P), P);
+        }
+    }
+    trim(&mut out);
+    out
+}
+
+/// `a - b` for polynomials over `GF(P)`
+fn poly_sub<const P: u64>(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut out = vec![0; a.len().max(b.len())];
+    for (i, o) in out.iter_mut().enumerate() {
+        let (x, y) = (
+            a.get(i).copied().unwrap_or(0),
+            b.get(i).copied().unwrap_or(0),
+        );
+        *o = sub_mod(x, y, P);
+    }
+    trim(&mut out);
+    out
+}
+
+/// `(a / b, a % b)` for trimmed polynomials over `GF(P)`, with `b` nonzero
+fn poly_div_rem<const P: u64>(a: &[u64], b: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    let lead_inv = pow_mod(b[b.len() - 1], P - 2, P);
+    let mut q = vec![0; (a.len() + 1).saturating_sub(b.len())];
+    let mut r = a.to_vec();
+    while r.len() >= b.len() {
+        let shift = r.len() - b.len();
+        let c = mul_mod(r[r.len() - 1], lead_inv, P);
+        q[shift] = c;
+        for (x, &y) in r[shift..].iter_mut().zip(b) {
+            *x = sub_mod(*x, mul_mod(c, y, P), P);
+        }
+        trim(&mut r);
+    }
+    trim(&mut q);
+    (q, r)
+}
+
+impl<const P: u64, const K: usize, M> Copy for Fpk<P, K, M> {}
+
+impl<const P: u64, const K: usize, M> Clone for Fpk<P, K, M> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<const P: u64, const K: usize, M> PartialEq for Fpk<P, K, M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<const P: u64, const K: usize, M> Eq for Fpk<P, K, M> {}
+
+impl<const P: u64, const K: usize, M> Hash for Fpk<P, K, M> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl<const P: u64, const K: usize, M> fmt::Debug for Fpk<P, K, M> {
+    /// Shows the coefficients, constant term first
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Fpk").field(&self.0).finish()
+    }
+}
+
+impl<const P: u64, const K: usize, M: IrreducibleModulus<P, K>> Add for Fpk<P, K, M> {
+    type Output = Self;
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut c = self.0;
+        for (x, &y) in c.iter_mut().zip(&rhs.0) {
+            *x = add_mod(*x, y, P);
+        }
+        Fpk(c, PhantomData)
+    }
+}
+impl<const P: u64, const K: usize, M: IrreducibleModulus<P, K>> Sub for Fpk<P, K, M> {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        let mut c = self.0;
+        for (x, &y) in c.iter_mut().zip(&rhs.0) {
+            *x = sub_mod(*x, y, P);
+        }
+        Fpk(c, PhantomData)
+    }
+}
+impl<const P: u64, const K: usize, M: IrreducibleModulus<P, K>> Mul for Fpk<P, K, M> {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: Self) -> Self::Output {
+        Fpk(
+            mul_poly::<P, K>(&self.0, &rhs.0, &Self::MODULUS),
+            PhantomData,
+        )
+    }
+}
+impl<const P: u64, const K: usize, M: IrreducibleModulus<P, K>> Div for Fpk<P, K, M> {
+    type Output = Self;
+    #[inline]
+    fn div(self, rhs: Self) -> Self::Output {
+        if rhs.is_zero() {
+            panic!("division by zero in Fpk");
+        }
+        Fpk(
+            mul_poly::<P, K>(&self.0, &rhs.inv().0, &Self::MODULUS),
+            PhantomData,
+        )
+    }
+}
+impl<const P: u64, const K: usize, M: IrreducibleModulus<P, K>> Neg for Fpk<P, K, M> {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self::Output {
+        Fpk(self.0.map(|c| sub_mod(0, c, P)), PhantomData)
+    }
+}
+
+impl<const P: u64, const K: usize, M: IrreducibleModulus<P, K>> Field for Fpk<P, K, M> {
+    #[inline]
+    fn zero() -> Self {
+        Fpk::new([0; K])
+    }
+    #[inline]
+    fn one() -> Self {
+        let mut c = [0; K];
+        c[0] = 1;
+        Fpk::new(c)
+    }
+
+    /// By the extended Euclidean algorithm on `self` and the modulus
+    ///
+    /// Time Complexity: `O(K^2)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(self * ret == Self::one())
+    )]
+    fn inv(self) -> Self {
+        if self.is_zero() {
+            panic!("inverse of zero in Fpk");
+        }
+        let mut f = Self::MODULUS.to_vec();
+        f.push(1);
+        let mut a = self.0.to_vec();
+        trim(&mut a);
+        // Invariant: t0·self = r0 and t1·self = r1 (mod f)
+        let (mut r0, mut r1) = (f, a);
+        let (mut t0, mut t1) = (Vec::new(), vec![1]);
+        while !r1.is_empty() {
+            let (q, r) = poly_div_rem::<P>(&r0, &r1);
+            let t = poly_sub::<P>(&t0, &poly_mul::<P>(&q, &t1));
+            (r0, r1) = (r1, r);
+            (t0, t1) = (t1, t);
+        }
+        // The modulus is irreducible, so the gcd r0 is a nonzero constant
+        let scale = pow_mod(r0[0], P - 2, P);
+        let mut c = [0; K];
+        for (x, &t) in c.iter_mut().zip(&t0) {
+            *x = mul_mod(t, scale, P);
+        }
+        Fpk(c, PhantomData)
+    }
+    #[inline]
+    fn characteristic() -> u64 {
+        Fp::<P>::MODULUS
+    }
+    #[inline]
+    fn order() -> Option<u64> {
+        let _ = Self::MODULUS;
+        u32::try_from(K).ok().and_then(|k| P.checked_pow(k))
+    }
+}
+
+impl<const P: u64, const K: usize, M: IrreducibleModulus<P, K>> FiniteField for Fpk<P, K, M> {
+    /// The coefficients are the base-`P` digits of `i`, constant term first
+    #[inline]
+    fn nth_element(mut i: u64) -> Self {
+        let mut c = [0; K];
+        for x in c.iter_mut() {
+            *x = i % P;
+            i /= P;
+        }
+        Fpk(c, PhantomData)
+    }
+
+    /// Starts from `nth_element(P) = x` and wraps around to the nonzero constants, which are
+    /// squares and have order dividing `P - 1` when `K >= 2`, so never generators
+    #[inline]
+    fn candidate(i: u64) -> Self {
+        let q = Self::order().expect("the order of a FiniteField must fit in a u64");
+        Self::nth_element((i - 1 + P - 1) % (q - 1) + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{ENABLED, Kind, catch};
+    use crate::number_theory::field::prime_factors;
+    use crate::sorting::prop_test::Rng;
+
+    /// Checks the generator search of [FiniteField] against the order of the group
+    fn has_full_order<F: FiniteField>(g: F) -> bool {
+        let q = F::order().unwrap();
+        prime_factors(q - 1)
+            .iter()
+            .all(|&p| g.pow((q - 1) / p) != F::one())
+    }
+
+    #[test]
+    fn smallest_irreducible_polynomials() {
+        // -1 is a non-residue modulo 7 but not modulo 5, where x^2 + x + 1 has discriminant -3
+        assert_eq!(smallest_irreducible::<7, 2>(), [1, 0]);
+        assert_eq!(smallest_irreducible::<5, 2>(), [1, 1]);
+        assert_eq!(smallest_irreducible::<2, 2>(), [1, 1]);
+        // x^3 + 1 has the root 1
+        assert_eq!(smallest_irreducible::<2, 3>(), [1, 0, 1]);
+        assert_eq!(smallest_irreducible::<2, 8>(), [1, 0, 0, 0, 1, 1, 0, 1]);
+        assert_eq!(smallest_irreducible::<3, 1>(), [0]);
+        // Every constant is a cube modulo 1_000_000_007, so no x^3 + c is irreducible
+        assert_eq!(smallest_irreducible::<1_000_000_007, 3>()[0], 1);
+
+        // x^4 + 1 has no roots over GF(3) but splits into two quadratics
+        assert!(!is_irreducible::<3, 4>(&[1, 0, 0, 0]));
+        // x^4 + x + 1 is irreducible over GF(2), x^4 + x^2 + 1 = (x^2 + x + 1)^2 is not
+        assert!(is_irreducible::<2, 4>(&[1, 1, 0, 0]));
+        assert!(!is_irreducible::<2, 4>(&[1, 0, 1, 0]));
+        // Degree 6 needs both the q = 2 and q = 3 checks: (x^3 + x + 1)(x^3 + x^2 + 1)
+        assert!(!is_irreducible::<2, 6>(&[1, 1, 1, 1, 1, 1]));
+        // (x^2 + x + 1)(x^4 + x + 1) has no roots either
+        assert!(!is_irreducible::<2, 6>(&[1, 0, 0, 1, 1, 1]));
+        assert!(is_irreducible::<2, 6>(&[1, 1, 0, 0, 0, 0]));
+    }
+
+    /// Checks the field axioms and the Frobenius automorphism on random elements
+    fn check_field<const P: u64, const K: usize, M: IrreducibleModulus<P, K>>(seed: u64) {
+        type F<const P: u64, const K: usize, M> = Fpk<P, K, M>;
+        let mut rng = Rng::new(seed);
+        let mut random = || F::<P, K, M>::new([0; K].map(|_: u64| rng.next_u64()));
+        for _ in 0..50 {
+            let (a, b, c) = (random(), random(), random());
+            assert_eq!((a + b) * c, a * c + b * c);
+            assert_eq!((a * b) * c, a * (b * c));
+            assert_eq!(a - b + b, a);
+            assert_eq!(a + (-a), F::zero());
+            if !a.is_zero() {
+                assert_eq!(a * a.inv(), F::one());
+                assert_eq!(b / a * a, b);
+            }
+            // x ↦ x^P is a field automorphism of order K
+            assert_eq!((a + b).frobenius(), a.frobenius() + b.frobenius());
+            assert_eq!((a * b).frobenius(), a.frobenius() * b.frobenius());
+            let mut y = a;
+            for _ in 0..K {
+                y = y.frobenius();
+            }
+            assert_eq!(y, a);
+        }
+    }
+
+    #[test]
+    fn field_axioms() {
+        check_field::<2, 1, SmallestIrreducible>(1);
+        check_field::<7, 3, SmallestIrreducible>(2);
+        check_field::<2, 8, SmallestIrreducible>(3);
+        check_field::<1_000_000_007, 2, SmallestIrreducible>(4);
+        check_field::<998_244_353, 4, SmallestIrreducible>(5);
+        check_field::<{ (1 << 61) - 1 }, 2, SmallestIrreducible>(6);
+    }
+
+    struct ISquaredPlusOne;
+    impl IrreducibleModulus<1_000_000_007, 2> for ISquaredPlusOne {
+        const COEFFICIENTS: [u64; 2] = [1, 0];
+    }
+
+    #[test]
+    fn gaussian_integers_modulo_p() {
+        type G = Fpk<1_000_000_007, 2, ISquaredPlusOne>;
+        let (a, b) = (G::new([3, 4]), G::new([1, 1_000_000_005]));
+        // (3 + 4i)(1 - 2i) = 11 - 2i
+        assert_eq!(a * b, G::new([11, 1_000_000_005]));
+        assert_eq!(G::x() * G::x(), -G::one());
+        assert_eq!(G::new([5, 0]).coefficients(), [Fp::new(5), Fp::zero()]);
+        assert!(G::new([5, 0]).is_constant());
+        assert_eq!(G::characteristic(), 1_000_000_007);
+        assert_eq!(G::order(), Some(1_000_000_007 * 1_000_000_007));
+
+        // Every element of GF(p) is a square in GF(p^2)
+        let root = G::new([1_000_000_006, 0]).sqrt().unwrap();
+        assert_eq!(root * root, -G::one());
+        let c = G::new([7, 3]);
+        let root = (c * c).sqrt().unwrap();
+        assert!(root == c || root == -c);
+        assert!(has_full_order(G::generator()));
+    }
+
+    #[test]
+    fn small_fields_by_brute_force() {
+        type F = Fpk<3, 2>;
+        let elements: Vec<F> = (0..9).map(F::nth_element).collect();
+        assert_eq!(elements[0], F::zero());
+        assert_eq!(elements[1], F::one());
+        assert_eq!(elements[3], F::x());
+        for &a in &elements {
+            let squares = elements.iter().filter(|&&r| r * r == a).count();
+            assert_eq!(a.sqrt().is_some(), squares > 0);
+            assert_eq!(a.is_square(), squares > 0);
+        }
+        let g = F::generator();
+        let powers: std::collections::HashSet<[u64; 2]> = (0..8).map(|e| g.pow(e).0).collect();
+        assert_eq!(powers.len(), 8);
+
+        type G = Fpk<7, 3>;
+        let g = G::generator();
+        assert!(has_full_order(g));
+        assert!(!g.is_constant());
+        assert_eq!(G::order(), Some(343));
+        assert_eq!(Fpk::<1_000_000_007, 3>::order(), None);
+    }
+
+    #[test]
+    fn unreduced_coefficient_violates_invariant() {
+        let a = Fpk::<7, 2>([9, 0], PhantomData);
+        let result = catch(move || a.is_constant());
+        assert_eq!(result.is_err(), ENABLED);
+        if let Err(v) = result {
+            assert_eq!(v.kind, Kind::Invariant);
+            assert_eq!(v.module, "safe_dsa::number_theory::extension_field");
+        }
+    }
+}
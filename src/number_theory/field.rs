@@ -5,52 +5,207 @@ Field
 
 Defines:
 - trait [Field]
-- struct [Fp]
+- trait [FiniteField], with square roots, the Legendre symbol and generators
+- struct [Fp], whose modulus is checked to be prime at compile time
+- struct [OutOfRange], the error of the exact conversion from [u64] to [Fp]
+- enum [FieldError], the error of the `checked_*` methods of [Field]
 
 Implements [Field] for:
 - [Fp]
-- [f64]
+- [f64], whose NaN is not equal to itself; see [Real](super::real::Real)
+
+Implements [FiniteField] for:
+- [Fp]
 */
 
-use contracts::debug_invariant;
-use std::ops::{Add, Div, Mul, Neg, Sub};
+#[cfg(any(debug_assertions, feature = "checked"))]
+use contracts::invariant;
+use std::error::Error;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::num::ParseIntError;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
 
-pub struct Assert<const B: bool>;
-pub trait IsTrue {}
-impl IsTrue for Assert<true> {}
+/// `a + b mod m`, for `a, b < m`
+#[inline]
+pub(crate) const fn add_mod(a: u64, b: u64, m: u64) -> u64 {
+    // The sum may not fit in u64 when m > 2^63, in which case it is above m
+    let (s, overflow) = a.overflowing_add(b);
+    if overflow || s >= m {
+        s.wrapping_sub(m)
+    } else {
+        s
+    }
+}
 
-const fn is_prime_u64(p: u64) -> bool {
-    if p < 2 {
-        return false;
+/// `a - b mod m`, for `a, b < m`
+#[inline]
+pub(crate) const fn sub_mod(a: u64, b: u64, m: u64) -> u64 {
+    if a >= b {
+        a - b
+    } else {
+        a.wrapping_sub(b).wrapping_add(m)
     }
-    if p % 2 == 0 {
-        return p == 2;
+}
+
+/// `a * b mod m`, for `a, b < m`. Callers pass a constant `m`, so only one branch is compiled.
+#[inline]
+pub(crate) const fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    if m <= 1 << 32 {
+        // Safe in u64 because (m-1)^2 < 2^64
+        a * b % m
+    } else {
+        ((a as u128 * b as u128) % m as u128) as u64
     }
-    let mut d: u64 = 3;
-    while d <= p / d {
-        if p % d == 0 {
-            return false;
+}
+
+/// `b^e mod m`
+pub(crate) const fn pow_mod(mut b: u64, mut e: u64, m: u64) -> u64 {
+    let mut acc = 1 % m;
+    b %= m;
+    while e > 0 {
+        if e & 1 == 1 {
+            acc = mul_mod(acc, b, m);
         }
-        d += 2;
+        b = mul_mod(b, b, m);
+        e >>= 1;
     }
-    true
+    acc
+}
+
+/// Greatest common divisor
+const fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// A nontrivial factor of an odd composite `n`, by Pollard's rho with Floyd's cycle finding
+fn pollard_rho(n: u64) -> u64 {
+    for c in 1..n {
+        let f = |x: u64| add_mod(mul_mod(x, x, n), c, n);
+        let (mut x, mut y, mut d) = (2, 2, 1);
+        while d == 1 {
+            x = f(x);
+            y = f(f(y));
+            d = gcd(x.abs_diff(y), n);
+        }
+        // d == n means x and y met modulo n itself; retry with another polynomial
+        if d != n {
+            return d;
+        }
+    }
+    unreachable!("{n} is not an odd composite")
+}
+
+/// The distinct prime factors of `n`, in increasing order
+///
+/// Time Complexity: `O(n^(1/4))` expected
+pub(crate) fn prime_factors(mut n: u64) -> Vec<u64> {
+    let mut primes = Vec::new();
+    for p in [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37] {
+        if n.is_multiple_of(p) {
+            primes.push(p);
+            while n.is_multiple_of(p) {
+                n /= p;
+            }
+        }
+    }
+    let mut pending = if n > 1 { vec![n] } else { vec![] };
+    while let Some(m) = pending.pop() {
+        if is_prime_u64(m) {
+            primes.push(m);
+        } else {
+            let d = pollard_rho(m);
+            pending.extend([d, m / d]);
+        }
+    }
+    primes.sort_unstable();
+    primes.dedup();
+    primes
 }
 
-// Ensure (P-1)^2 <= u64::MAX without overflowing
-const fn square_bound_ok(p: u64) -> bool {
-    if p == 0 {
+/// Deterministic Miller-Rabin: the first 12 primes as bases are enough for every `u64`
+pub(crate) const fn is_prime_u64(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
         return false;
     }
-    let m = p - 1;
-    m * m <= u64::MAX
+    let mut i = 0;
+    while i < BASES.len() {
+        if n.is_multiple_of(BASES[i]) {
+            return n == BASES[i];
+        }
+        i += 1;
+    }
+    // n - 1 = d * 2^s with d odd
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    let mut i = 0;
+    while i < BASES.len() {
+        let mut x = pow_mod(BASES[i], d, n);
+        let mut r = 1;
+        if x != 1 && x != n - 1 {
+            while r < s {
+                x = mul_mod(x, x, n);
+                if x == n - 1 {
+                    break;
+                }
+                r += 1;
+            }
+            if r == s {
+                return false;
+            }
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Why a `checked_*` method of [Field] failed
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FieldError {
+    /// Division by zero, inverting zero, or raising zero to a negative power
+    DivisionByZero,
+    /// The result is not a number, such as `∞ - ∞` in [Real](super::real::Real)
+    NotANumber,
+    /// The result does not fit in the representation, such as a
+    /// [Rational](super::rational::Rational) whose numerator exceeds [i64]
+    Overflow,
 }
 
-const fn valid_modulus(p: u64) -> bool {
-    square_bound_ok(p) && is_prime_u64(p)
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldError::DivisionByZero => write!(f, "division by zero"),
+            FieldError::NotANumber => write!(f, "the result is not a number"),
+            FieldError::Overflow => write!(f, "the result overflows"),
+        }
+    }
 }
 
-/// A math Field: supports +, -, *, / (except by 0), additive & multiplicative identities,
-/// and inverses. Equality should be a proper equivalence relation.
+impl Error for FieldError {}
+
+/**
+A math Field: supports +, -, *, / (except by 0), additive & multiplicative identities,
+and inverses. Equality should be a proper equivalence relation.
+
+# Panics
+
+[inv] of zero and [powi] of zero with a negative exponent panic in every implementation of
+this crate, and so does dividing by zero with `/`, except for [f64]: it follows IEEE 754, so
+`1.0 / 0.0` is infinite and `0.0 / 0.0` is NaN, which is not even equal to itself. Use
+[Real](super::real::Real) for real numbers that are never NaN and whose `/` panics on zero.
+[FiniteField] panics on a field without a finite [order](Field::order) too. On untrusted
+input, use [try_inv](Field::try_inv), [checked_inv](Field::checked_inv),
+[checked_div](Field::checked_div) and [checked_powi](Field::checked_powi) instead, which never
+panic. [batch_inv](Field::batch_inv) skips zeros, so it never panics either.
+
+[inv]: Field::inv
+[powi]: Field::powi
+*/
 pub trait Field:
     Copy
     + Clone
@@ -68,6 +223,12 @@ pub trait Field:
     /// Multiplicative identity
     fn one() -> Self;
 
+    /// The least `n > 0` with `n·1 = 0`, or 0 if there is none
+    fn characteristic() -> u64;
+
+    /// The number of elements, or `None` if the field is infinite or too large for a `u64`
+    fn order() -> Option<u64>;
+
     /// Is element the additive identity?
     #[inline]
     fn is_zero(self) -> bool {
@@ -80,17 +241,119 @@ pub trait Field:
         Self::one() / self
     }
 
+    /// Multiplicative inverse, or `None` if `self` is zero
+    #[inline]
+    fn try_inv(self) -> Option<Self> {
+        if self.is_zero() {
+            None
+        } else {
+            Some(self.inv())
+        }
+    }
+
+    /// Multiplicative inverse, or [FieldError::DivisionByZero] if `self` is zero
+    #[inline]
+    fn checked_inv(self) -> Result<Self, FieldError> {
+        self.try_inv().ok_or(FieldError::DivisionByZero)
+    }
+
+    /// `self / rhs`, or [FieldError::DivisionByZero] if `rhs` is zero
+    #[inline]
+    fn checked_div(self, rhs: Self) -> Result<Self, FieldError> {
+        if rhs.is_zero() {
+            Err(FieldError::DivisionByZero)
+        } else {
+            Ok(self / rhs)
+        }
+    }
+
+    /// `self^n`, or [FieldError::DivisionByZero] if `self` is zero and `n` is negative
+    #[inline]
+    fn checked_powi(self, n: i64) -> Result<Self, FieldError> {
+        if n < 0 && self.is_zero() {
+            Err(FieldError::DivisionByZero)
+        } else {
+            Ok(self.powi(n))
+        }
+    }
+
+    /// Inverts every nonzero element of `values` in place, leaving zeros as they are, with
+    /// Montgomery's trick: a single inversion and three multiplications per element.
+    ///
+    /// Time Complexity: `O(n)`
+    fn batch_inv(values: &mut [Self]) {
+        // prefix[i] is the product of the nonzero elements of values[..i]
+        let mut prefix = Vec::with_capacity(values.len());
+        let mut acc = Self::one();
+        for &x in values.iter() {
+            prefix.push(acc);
+            if !x.is_zero() {
+                acc = acc * x;
+            }
+        }
+        // inv is the inverse of the product of the nonzero elements of values[..=i]
+        let mut inv = acc.inv();
+        for (x, before) in values.iter_mut().zip(prefix).rev() {
+            if !x.is_zero() {
+                (*x, inv) = (inv * before, inv * *x);
+            }
+        }
+    }
+
     /// Integer exponentiation
     #[inline]
     fn powi(self, n: i64) -> Self {
         if n == 0 {
             return Self::one();
         }
-        if n < 0 {
-            return self.inv().powi(-n);
+        // unsigned_abs, since -i64::MIN overflows
+        let mut base = if n < 0 { self.inv() } else { self };
+        let mut e = n.unsigned_abs();
+        let mut acc = Self::one();
+        while e > 0 {
+            if e & 1 == 1 {
+                acc = acc * base;
+            }
+            e >>= 1;
+            // Squaring past the last bit could overflow in exact fields such as Rational
+            if e > 0 {
+                base = base * base;
+            }
         }
+        acc
+    }
+}
+
+/// The order of a [FiniteField]
+fn finite_order<F: FiniteField>() -> u64 {
+    F::order().expect("the order of a FiniteField must fit in a u64")
+}
+
+/**
+A [Field] with finitely many elements, whose [order](Field::order) `q` must be `Some`
+
+Every finite field has prime power order `q = p^k`, where `p` is the characteristic, and its
+nonzero elements form a cyclic group of order `q - 1`. The provided methods build on that.
+*/
+pub trait FiniteField: Field {
+    /// The `i`-th element of a fixed enumeration of the field, for `i < q`, starting with zero
+    /// and one
+    fn nth_element(i: u64) -> Self;
+
+    /// The `i`-th nonzero element tried by searches such as [generator](FiniteField::generator),
+    /// for `1 <= i < q`: by default the [nth_element](FiniteField::nth_element) order.
+    /// Implementations may reorder it to try promising elements first.
+    #[inline]
+    fn candidate(i: u64) -> Self {
+        Self::nth_element(i)
+    }
+
+    /// `self^e`, for exponents up to `q - 1` that may not fit in [Field::powi]
+    ///
+    /// Time Complexity: `O(log e)`
+    #[inline]
+    fn pow(self, mut e: u64) -> Self {
         let mut base = self;
-        let mut e = n as u64;
         let mut acc = Self::one();
         while e > 0 {
             if e & 1 == 1 {
@@ -101,47 +364,176 @@ pub trait Field:
         }
         acc
     }
+
+    /// The Frobenius automorphism `x ↦ x^p`, which is the identity on prime fields
+    #[inline]
+    fn frobenius(self) -> Self {
+        self.pow(Self::characteristic())
+    }
+
+    /// The quadratic character: 0 for zero, 1 for nonzero squares and -1 otherwise.
+    /// It is the Legendre symbol `(x/p)` when `q = p` is an odd prime.
+    ///
+    /// Time Complexity: `O(log q)`
+    fn legendre(self) -> i8 {
+        let q = finite_order::<Self>();
+        if self.is_zero() {
+            0
+        } else if q.is_multiple_of(2) || self.pow((q - 1) / 2) == Self::one() {
+            // Squaring is a bijection in characteristic 2
+            1
+        } else {
+            -1
+        }
+    }
+
+    /// Whether `self` has a square root
+    #[inline]
+    fn is_square(self) -> bool {
+        self.legendre() >= 0
+    }
+
+    /// A square root of `self`, or `None` if there is none. The other root is its negation.
+    ///
+    /// Time Complexity: `O(log^2 q)`, by Tonelli-Shanks
+    fn sqrt(self) -> Option<Self> {
+        let q = finite_order::<Self>();
+        if self.is_zero() {
+            return Some(self);
+        }
+        if q.is_multiple_of(2) {
+            // x^q = x, so x^(q/2) squares to x
+            return Some(self.pow(q / 2));
+        }
+        if self.legendre() != 1 {
+            return None;
+        }
+        // q - 1 = odd * 2^s
+        let s = (q - 1).trailing_zeros();
+        let odd = (q - 1) >> s;
+        // Half of the nonzero elements are non-squares
+        let z = (1..q)
+            .map(Self::candidate)
+            .find(|z| z.legendre() == -1)
+            .expect("an odd order field has a non-square");
+
+        // Invariant: r^2 = self * t, t has order dividing 2^(m-1), c has order 2^m
+        let mut m = s;
+        let mut c = z.pow(odd);
+        let mut t = self.pow(odd);
+        let mut r = self.pow(odd.div_ceil(2));
+        while t != Self::one() {
+            // The order of t is 2^i, with i < m
+            let mut i = 0;
+            let mut t2 = t;
+            while t2 != Self::one() {
+                t2 = t2 * t2;
+                i += 1;
+            }
+            let mut b = c;
+            for _ in 0..m - i - 1 {
+                b = b * b;
+            }
+            m = i;
+            c = b * b;
+            t = t * c;
+            r = r * b;
+        }
+        Some(r)
+    }
+
+    /// A generator of the multiplicative group, the first in [candidate](Self::candidate)
+    /// order. For [Fp] it is the smallest primitive root modulo `P`.
+    ///
+    /// Time Complexity: `O(q^(1/4))` expected to factor `q - 1`, then `O(log^2 q)` per candidate
+    fn generator() -> Self {
+        let q = finite_order::<Self>();
+        let factors = prime_factors(q - 1);
+        (1..q)
+            .map(Self::candidate)
+            .find(|g| factors.iter().all(|&p| g.pow((q - 1) / p) != Self::one()))
+            .expect("the multiplicative group of a finite field is cyclic")
+    }
 }
 
-/// Integers modulo prime P
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub struct Fp<const P: u64>(pub u64);
+/**
+Integers modulo prime P
 
-#[debug_invariant(valid_modulus(P))]
-#[debug_invariant(self.0 < P)]
+`P` must be prime, which is checked at compile time when `Fp<P>` is used. Composite moduli
+belong in [Zn](super::zn::Zn).
+
+Elements are ordered and hashed by their canonical value in `0..P`, so they can be used as map
+keys and sorted. Arithmetic mixes with `u64` scalars, which are reduced modulo `P` first.
+
+```
+use safe_dsa::number_theory::field::Fp;
+type F = Fp<1_000_000_007>;
+let x: F = "-1".parse().unwrap();
+assert_eq!(x + 1u64, F::default());
+assert_eq!((1..=10u64).map(F::new).product::<F>(), F::from(3_628_800u32));
+assert_eq!((2u64 * x).to_string(), "1000000005");
+```
+
+A composite modulus does not compile, and neither does setting the value directly, which would
+bypass the reduction modulo `P`:
+```compile_fail
+use safe_dsa::number_theory::field::Fp;
+let x = Fp::<6>::new(5);
+```
+```compile_fail
+use safe_dsa::number_theory::field::Fp;
+let x = Fp::<7>(9);
+```
+*/
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fp<const P: u64>(u64);
+
+impl<const P: u64> Fp<P> {
+    /// `P`, rejected at compile time unless it is prime. Every operation goes through it.
+    pub const MODULUS: u64 = {
+        assert!(
+            is_prime_u64(P),
+            "the modulus of Fp<P> must be prime; use Zn<N> for composite moduli"
+        );
+        P
+    };
+}
+
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::invariant(self.0 < P)
+)]
 impl<const P: u64> Fp<P> {
     #[inline]
     pub fn new(x: u64) -> Self {
-        Fp::<P>(x % P)
+        Fp::<P>(x % Self::MODULUS)
     }
 
     #[inline]
     pub fn value(self) -> u64 {
+        let _ = Self::MODULUS;
         self.0
     }
 
     #[inline]
     fn add_raw(self, rhs: Self) -> Self {
-        let mut s = self.0 + rhs.0;
-        if s >= P {
-            s -= P;
-        }
-        Fp::<P>(s)
+        Fp::<P>(add_mod(self.0, rhs.0, Self::MODULUS))
     }
 
     #[inline]
     fn sub_raw(self, rhs: Self) -> Self {
-        if self.0 >= rhs.0 {
-            Fp::<P>(self.0 - rhs.0)
-        } else {
-            Fp::<P>(self.0 + P - rhs.0)
-        }
+        Fp::<P>(sub_mod(self.0, rhs.0, Self::MODULUS))
     }
 
     #[inline]
     fn mul_raw(self, rhs: Self) -> Self {
-        // Safe in u64 because 0 <= a,b < P and (P-1)^2 <= u64::MAX
-        Fp::<P>((self.0 * rhs.0) % P)
+        Fp::<P>(mul_mod(self.0, rhs.0, Self::MODULUS))
+    }
+
+    /// `self^e`, for exponents that may not fit in [Field::powi]
+    #[inline]
+    fn pow_raw(self, e: u64) -> Self {
+        Fp::<P>(pow_mod(self.0, e, Self::MODULUS))
     }
 }
 
@@ -174,36 +566,206 @@ impl<const P: u64> Div for Fp<P> {
             panic!("division by zero in Fp");
         }
         // Fermat's Little Theorem
-        self * Fp::<P>::powi(rhs, (P - 2) as i64)
+        self * rhs.pow_raw(Self::MODULUS - 2)
     }
 }
 impl<const P: u64> Neg for Fp<P> {
     type Output = Self;
     #[inline]
     fn neg(self) -> Self::Output {
-        if self.0 == 0 {
-            self
-        } else {
-            Fp::<P>(P - self.0)
-        }
+        Fp::<P>(sub_mod(0, self.0, Self::MODULUS))
     }
 }
 
+/// Implements `$OpAssign` for [Fp], and `$Op`/`$OpAssign` between [Fp] and `u64` on either side
+macro_rules! impl_fp_assign_and_scalar_ops {
+    ($($Op:ident $op:ident $OpAssign:ident $op_assign:ident),* $(,)?) => {$(
+        impl<const P: u64> $OpAssign for Fp<P> {
+            #[inline]
+            fn $op_assign(&mut self, rhs: Self) {
+                *self = $Op::$op(*self, rhs);
+            }
+        }
+        impl<const P: u64> $Op<u64> for Fp<P> {
+            type Output = Self;
+            #[inline]
+            fn $op(self, rhs: u64) -> Self::Output {
+                $Op::$op(self, Fp::<P>::new(rhs))
+            }
+        }
+        impl<const P: u64> $Op<Fp<P>> for u64 {
+            type Output = Fp<P>;
+            #[inline]
+            fn $op(self, rhs: Fp<P>) -> Self::Output {
+                $Op::$op(Fp::<P>::new(self), rhs)
+            }
+        }
+        impl<const P: u64> $OpAssign<u64> for Fp<P> {
+            #[inline]
+            fn $op_assign(&mut self, rhs: u64) {
+                *self = $Op::$op(*self, rhs);
+            }
+        }
+    )*};
+}
+
+impl_fp_assign_and_scalar_ops!(
+    Add add AddAssign add_assign,
+    Sub sub SubAssign sub_assign,
+    Mul mul MulAssign mul_assign,
+    Div div DivAssign div_assign,
+);
+
 impl<const P: u64> Field for Fp<P> {
     #[inline]
     fn zero() -> Self {
-        Fp::<P>(0)
+        Fp::<P>::new(0)
     }
     #[inline]
     fn one() -> Self {
-        Fp::<P>(1 % P)
+        Fp::<P>::new(1)
     }
     #[inline]
     fn inv(self) -> Self {
         if self.0 == 0 {
             panic!("inverse of zero in Fp");
         }
-        Fp::<P>::powi(self, (P - 2) as i64)
+        self.pow_raw(Self::MODULUS - 2)
+    }
+    #[inline]
+    fn characteristic() -> u64 {
+        Self::MODULUS
+    }
+    #[inline]
+    fn order() -> Option<u64> {
+        Some(Self::MODULUS)
+    }
+}
+
+impl<const P: u64> FiniteField for Fp<P> {
+    #[inline]
+    fn nth_element(i: u64) -> Self {
+        Fp::<P>::new(i)
+    }
+    #[inline]
+    fn pow(self, e: u64) -> Self {
+        self.pow_raw(e)
+    }
+}
+
+impl<const P: u64> Default for Fp<P> {
+    /// Zero
+    #[inline]
+    fn default() -> Self {
+        Fp::<P>::zero()
+    }
+}
+
+impl<const P: u64> fmt::Display for Fp<P> {
+    /// Shows the canonical value, in `0..P`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.value(), f)
+    }
+}
+
+impl<const P: u64> FromStr for Fp<P> {
+    type Err = ParseIntError;
+
+    /// Parses a decimal integer in the range of [i128], possibly negative, and reduces it
+    /// modulo `P`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<i128>().map(Fp::<P>::from)
+    }
+}
+
+impl<const P: u64> From<u32> for Fp<P> {
+    #[inline]
+    fn from(x: u32) -> Self {
+        Fp::<P>::new(x as u64)
+    }
+}
+
+impl<const P: u64> From<i64> for Fp<P> {
+    /// Reduces `x` modulo `P`, so negative values map to `P - (-x mod P)`
+    #[inline]
+    fn from(x: i64) -> Self {
+        Fp::<P>::from(x as i128)
+    }
+}
+
+impl<const P: u64> From<i128> for Fp<P> {
+    /// Reduces `x` modulo `P`, so negative values map to `P - (-x mod P)`
+    #[inline]
+    fn from(x: i128) -> Self {
+        Fp::<P>(x.rem_euclid(Self::MODULUS as i128) as u64)
+    }
+}
+
+impl<const P: u64> From<Fp<P>> for u64 {
+    /// The canonical value, in `0..P`
+    #[inline]
+    fn from(x: Fp<P>) -> Self {
+        x.value()
+    }
+}
+
+/// The value given to [`Fp::try_from`] was not already reduced modulo `P`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct OutOfRange {
+    pub value: u64,
+    pub modulus: u64,
+}
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is not below the modulus {}",
+            self.value, self.modulus
+        )
+    }
+}
+
+impl Error for OutOfRange {}
+
+impl<const P: u64> TryFrom<u64> for Fp<P> {
+    type Error = OutOfRange;
+
+    /// Converts `x` exactly, failing instead of reducing when `x >= P`
+    #[inline]
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        if x < Self::MODULUS {
+            Ok(Fp::<P>(x))
+        } else {
+            Err(OutOfRange {
+                value: x,
+                modulus: P,
+            })
+        }
+    }
+}
+
+impl<const P: u64> Sum for Fp<P> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Fp::<P>::zero(), Add::add)
+    }
+}
+
+impl<'a, const P: u64> Sum<&'a Fp<P>> for Fp<P> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl<const P: u64> Product for Fp<P> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Fp::<P>::one(), Mul::mul)
+    }
+}
+
+impl<'a, const P: u64> Product<&'a Fp<P>> for Fp<P> {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().product()
     }
 }
 
@@ -224,6 +786,15 @@ impl Field for f64 {
         }
         1.0 / self
     }
+
+    #[inline]
+    fn characteristic() -> u64 {
+        0
+    }
+    #[inline]
+    fn order() -> Option<u64> {
+        None
+    }
 }
 
 #[cfg(test)]
@@ -240,4 +811,263 @@ mod tests {
         assert_eq!((a - b).value(), 5);
         assert_eq!((a / b).value(), (a * b.inv()).value());
     }
+
+    #[test]
+    fn miller_rabin_agrees_with_trial_division() {
+        let trial = |n: u64| {
+            n >= 2
+                && (2..)
+                    .take_while(|d| d * d <= n)
+                    .all(|d| !n.is_multiple_of(d))
+        };
+        for n in 0..5000 {
+            assert_eq!(is_prime_u64(n), trial(n), "{n}");
+        }
+        assert!(is_prime_u64((1 << 61) - 1));
+        assert!(is_prime_u64(18_446_744_073_709_551_557));
+        assert!(is_prime_u64(998_244_353));
+        // Strong pseudoprimes to the first few prime bases
+        assert!(!is_prime_u64(3_215_031_751));
+        assert!(!is_prime_u64(3_825_123_056_546_413_051));
+        assert!(!is_prime_u64(u64::MAX));
+    }
+
+    /// Checks the operations of `Fp<P>` against `u128` arithmetic
+    fn check_wide<const P: u64>(values: &[u64]) {
+        let p = P as u128;
+        for &x in values {
+            for &y in values {
+                let (a, b) = (Fp::<P>::new(x), Fp::<P>::new(y));
+                let (x, y) = (x as u128 % p, y as u128 % p);
+                assert_eq!((a + b).value() as u128, (x + y) % p);
+                assert_eq!((a - b).value() as u128, (x + p - y) % p);
+                assert_eq!((a * b).value() as u128, x * y % p);
+                assert_eq!((-a).value() as u128, (p - x) % p);
+                if y != 0 {
+                    assert_eq!(a / b * b, a);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fp_large_moduli() {
+        const M61: u64 = (1 << 61) - 1;
+        const MAX: u64 = 18_446_744_073_709_551_557;
+        let values = [
+            0,
+            1,
+            2,
+            12345,
+            1 << 32,
+            (1 << 61) - 2,
+            1 << 63,
+            MAX - 1,
+            u64::MAX,
+        ];
+        check_wide::<M61>(&values);
+        check_wide::<MAX>(&values);
+        check_wide::<4_294_967_291>(&values);
+        check_wide::<998_244_353>(&values);
+
+        let a = Fp::<M61>::new(1 << 40);
+        assert_eq!(a * a.inv(), Fp::one());
+        assert_eq!(Fp::<MAX>::new(MAX - 1).inv(), Fp::new(MAX - 1));
+    }
+
+    #[test]
+    fn fp_standard_traits() {
+        type F = Fp<1_000_000_007>;
+        let mut a = F::new(10);
+        a += F::new(5);
+        a -= 20;
+        assert_eq!(a, F::from(-5i64));
+        a *= a;
+        a /= 5;
+        assert_eq!(a.value(), 5);
+        assert_eq!(3u64 - a, -F::new(2));
+        assert_eq!(a * 2, F::new(10));
+        assert_eq!(a / 5 + 1, F::new(2));
+
+        assert_eq!("-1".parse::<F>(), Ok(F::new(1_000_000_006)));
+        assert_eq!("+1000000008".parse::<F>(), Ok(F::new(1)));
+        assert_eq!(
+            "-170141183460469231731687303715884105728".parse::<F>(),
+            Ok(F::from(i128::MIN))
+        );
+        assert!("".parse::<F>().is_err());
+        assert!("12a".parse::<F>().is_err());
+        assert_eq!(F::from(u32::MAX).value(), u32::MAX as u64 % 1_000_000_007);
+        assert_eq!(F::from(i64::MIN) + F::from(i64::MAX), -F::one());
+        assert_eq!(u64::from(F::new(7)), 7);
+        assert_eq!(F::try_from(1_000_000_006u64), Ok(F::new(1_000_000_006)));
+        assert_eq!(
+            F::try_from(1_000_000_007u64),
+            Err(OutOfRange {
+                value: 1_000_000_007,
+                modulus: 1_000_000_007
+            })
+        );
+        assert_eq!(F::default(), F::zero());
+        assert_eq!(format!("{:>4}", F::new(42)), "  42");
+        assert_eq!(F::from(-3i64).to_string(), "1000000004");
+    }
+
+    #[test]
+    fn fp_sum_product_order_and_hash() {
+        use crate::sorting::{heap_sort, merge_sort};
+        use std::collections::{BTreeSet, HashMap};
+
+        let values: Vec<F7> = [5, 3, 6, 0, 3, 1].into_iter().map(F7::new).collect();
+        assert_eq!(values.iter().sum::<F7>(), F7::new(4));
+        assert_eq!(values.iter().skip(4).product::<F7>(), F7::new(3));
+        assert_eq!(
+            values
+                .iter()
+                .copied()
+                .filter(|x| !x.is_zero())
+                .product::<F7>(),
+            F7::new(4)
+        );
+        assert_eq!(Vec::<F7>::new().into_iter().product::<F7>(), F7::one());
+
+        let expected: Vec<F7> = [0, 1, 3, 3, 5, 6].into_iter().map(F7::new).collect();
+        let mut sorted = values.clone();
+        heap_sort::sort(&mut sorted);
+        assert_eq!(sorted, expected);
+        let mut sorted = values.clone();
+        merge_sort::sort(&mut sorted);
+        assert_eq!(sorted, expected);
+        assert!(F7::new(6) > F7::new(8));
+
+        let mut counts = HashMap::new();
+        for &x in &values {
+            *counts.entry(x).or_insert(0) += 1;
+        }
+        assert_eq!(counts[&F7::new(10)], 2);
+        let set: BTreeSet<F7> = values.into_iter().collect();
+        assert_eq!(set.len(), 5);
+    }
+
+    #[test]
+    fn prime_factors_agree_with_trial_division() {
+        let trial = |mut n: u64| {
+            let mut primes = Vec::new();
+            let mut d = 2;
+            while d * d <= n {
+                if n.is_multiple_of(d) {
+                    primes.push(d);
+                    while n.is_multiple_of(d) {
+                        n /= d;
+                    }
+                }
+                d += 1;
+            }
+            if n > 1 {
+                primes.push(n);
+            }
+            primes
+        };
+        for n in 1..3000 {
+            assert_eq!(prime_factors(n), trial(n), "{n}");
+        }
+        assert_eq!(
+            prime_factors(u64::MAX),
+            [3, 5, 17, 257, 641, 65537, 6_700_417]
+        );
+        assert_eq!(prime_factors(998_244_352), [2, 7, 17]);
+        // Product of two primes near 2^32
+        assert_eq!(
+            prime_factors(4_294_967_291 * 4_294_967_279),
+            [4_294_967_279, 4_294_967_291]
+        );
+        assert_eq!(
+            prime_factors(4_294_967_291 * 4_294_967_291),
+            [4_294_967_291]
+        );
+    }
+
+    /// Checks [FiniteField] on `Fp<P>` against Euler's criterion and brute force over `values`
+    fn check_finite_field<const P: u64>(values: impl Iterator<Item = u64>) {
+        let g = Fp::<P>::generator();
+        for p in prime_factors(P - 1) {
+            assert_ne!(g.pow((P - 1) / p), Fp::one(), "{P}");
+        }
+        for x in values {
+            let a = Fp::<P>::new(x);
+            assert_eq!(a.frobenius(), a);
+            match a.sqrt() {
+                Some(r) => {
+                    assert_eq!(r * r, a, "{P} {x}");
+                    assert!(a.is_square());
+                }
+                None => assert_eq!(a.legendre(), -1, "{P} {x}"),
+            }
+            // A square times a non-square is a non-square
+            if !a.is_zero() {
+                assert_eq!((a * a * g).legendre(), -1, "{P} {x}");
+            }
+        }
+    }
+
+    #[test]
+    fn square_roots_and_generators() {
+        assert_eq!(Fp::<2>::generator(), Fp::one());
+        assert_eq!(Fp::<3>::generator().value(), 2);
+        assert_eq!(F7::generator().value(), 3);
+        assert_eq!(Fp::<998_244_353>::generator().value(), 3);
+        assert_eq!(Fp::<1_000_000_007>::generator().value(), 5);
+        assert_eq!(Fp::<2>::new(1).sqrt(), Some(Fp::one()));
+
+        let squares: Vec<i8> = (0..7).map(|x| F7::new(x).legendre()).collect();
+        assert_eq!(squares, [0, 1, 1, -1, 1, -1, -1]);
+
+        check_finite_field::<3>(0..3);
+        check_finite_field::<13>(0..13);
+        // 2^8 + 1: Tonelli-Shanks runs its longest loop when q - 1 is a power of two
+        check_finite_field::<257>(0..257);
+        check_finite_field::<998_244_353>((0..200).map(|i| i * 4_990_000 + i));
+        check_finite_field::<{ (1 << 61) - 1 }>((0..50).map(|i| i << 55 | i));
+        check_finite_field::<18_446_744_073_709_551_557>((0..50).map(|i| u64::MAX - i * i));
+    }
+
+    #[test]
+    fn batch_inversion() {
+        type F = Fp<1_000_000_007>;
+        let mut values: Vec<F> = [3, 0, 1, 1_000_000_006, 0, 123_456_789]
+            .into_iter()
+            .map(F::new)
+            .collect();
+        let expected: Vec<F> = values.iter().map(|x| x.try_inv().unwrap_or(*x)).collect();
+        F::batch_inv(&mut values);
+        assert_eq!(values, expected);
+        assert_eq!(F::zero().try_inv(), None);
+        assert_eq!(F::new(2).try_inv(), Some(F::new(500_000_004)));
+
+        let mut empty: [F; 0] = [];
+        F::batch_inv(&mut empty);
+        let mut reals = [0.5, -4.0, 0.0];
+        f64::batch_inv(&mut reals);
+        assert_eq!(reals, [2.0, -0.25, 0.0]);
+        assert_eq!((f64::characteristic(), f64::order()), (0, None));
+        assert_eq!((F7::characteristic(), F7::order()), (7, Some(7)));
+    }
+
+    #[test]
+    fn checked_operations_do_not_panic() {
+        let (a, zero) = (F7::new(3), F7::zero());
+        assert_eq!(a.checked_div(zero), Err(FieldError::DivisionByZero));
+        assert_eq!(a.checked_div(F7::new(2)), Ok(F7::new(5)));
+        assert_eq!(zero.checked_inv(), Err(FieldError::DivisionByZero));
+        assert_eq!(a.checked_inv(), Ok(F7::new(5)));
+        assert_eq!(zero.checked_powi(-1), Err(FieldError::DivisionByZero));
+        assert_eq!(zero.checked_powi(0), Ok(F7::one()));
+        assert_eq!(zero.checked_powi(5), Ok(zero));
+        assert_eq!(a.checked_powi(-2), Ok(F7::new(4)));
+        // 3^(i64::MIN) = 3^(-2^63), and 3 has order 6
+        assert_eq!(a.checked_powi(i64::MIN), Ok(F7::new(5).powi(2)));
+        assert_eq!(0.0.checked_div(0.0), Err(FieldError::DivisionByZero));
+        assert_eq!(1.0.checked_div(4.0), Ok(0.25));
+        assert_eq!(FieldError::DivisionByZero.to_string(), "division by zero");
+    }
 }
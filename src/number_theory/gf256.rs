@@ -0,0 +1,317 @@
+/*!
+Number Theory
+
+The Field with 256 Elements
+
+Defines:
+- struct [Gf256], a byte as an element of `GF(2^8)`
+
+Implements [Field] and [FiniteField] for:
+- [Gf256]
+
+The modulus is `x^8 + x^4 + x^3 + x^2 + 1` (`0x11D`), the one used by Reed–Solomon codes
+such as those of QR codes and RAID 6, for which `x = 2` generates the multiplicative group.
+Addition is XOR, and multiplication and division add and subtract discrete logarithms in
+tables built at compile time. The same field, with the bits of a byte as coefficients, is
+[Fpk](crate::number_theory::extension_field::Fpk)`<2, 8, M>` for a marker `M` naming
+`0x11D`, which is slower but does not need the tables.
+*/
+
+use crate::number_theory::field::{Field, FiniteField};
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// The low 8 bits of the modulus `x^8 + x^4 + x^3 + x^2 + 1`
+const POLY: u16 = 0x11D;
+
+/// `EXP[i] = 2^i`, repeated so that the sum of two logarithms needs no reduction
+const EXP: [u8; 512] = {
+    let mut exp = [0; 512];
+    let mut x: u16 = 1;
+    let mut i = 0;
+    while i < 255 {
+        exp[i] = x as u8;
+        exp[i + 255] = x as u8;
+        x <<= 1;
+        if x & 0x100 != 0 {
+            x ^= POLY;
+        }
+        i += 1;
+    }
+    exp
+};
+
+/// `LOG[x]` is the `i < 255` with `2^i = x`, for nonzero `x`
+const LOG: [u8; 256] = {
+    let mut log = [0; 256];
+    let mut i = 0;
+    while i < 255 {
+        log[EXP[i] as usize] = i as u8;
+        i += 1;
+    }
+    log
+};
+
+/// An element of `GF(2^8)`, whose bits are the coefficients of a polynomial over `GF(2)`
+///
+/// ```
+/// use safe_dsa::number_theory::field::Field;
+/// use safe_dsa::number_theory::gf256::Gf256;
+///
+/// let (a, b) = (Gf256(0x53), Gf256(0xCA));
+/// assert_eq!(a + b, Gf256(0x99));
+/// assert_eq!(a * b / b, a);
+/// assert_eq!(a * a.inv(), Gf256::one());
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Gf256(pub u8);
+
+impl Gf256 {
+    /// The generator `x = 2` of the multiplicative group, whose powers are tabulated
+    pub const GENERATOR: Gf256 = Gf256(2);
+
+    /// `2^e`
+    #[inline]
+    pub fn exp(e: u64) -> Self {
+        Gf256(EXP[(e % 255) as usize])
+    }
+
+    /// The `i < 255` with `2^i = self`, or `None` for zero
+    #[inline]
+    pub fn log(self) -> Option<u8> {
+        (self.0 != 0).then(|| LOG[self.0 as usize])
+    }
+}
+
+impl Add for Gf256 {
+    type Output = Self;
+    #[inline]
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, rhs: Self) -> Self::Output {
+        Gf256(self.0 ^ rhs.0)
+    }
+}
+impl Sub for Gf256 {
+    type Output = Self;
+    #[inline]
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, rhs: Self) -> Self::Output {
+        // In characteristic 2, subtraction is addition
+        Gf256(self.0 ^ rhs.0)
+    }
+}
+impl Mul for Gf256 {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: Self) -> Self::Output {
+        if self.0 == 0 || rhs.0 == 0 {
+            return Gf256(0);
+        }
+        Gf256(EXP[LOG[self.0 as usize] as usize + LOG[rhs.0 as usize] as usize])
+    }
+}
+impl Div for Gf256 {
+    type Output = Self;
+    #[inline]
+    fn div(self, rhs: Self) -> Self::Output {
+        if rhs.0 == 0 {
+            panic!("division by zero in Gf256");
+        }
+        if self.0 == 0 {
+            return Gf256(0);
+        }
+        Gf256(EXP[LOG[self.0 as usize] as usize + 255 - LOG[rhs.0 as usize] as usize])
+    }
+}
+impl Neg for Gf256 {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self::Output {
+        self
+    }
+}
+
+impl Field for Gf256 {
+    #[inline]
+    fn zero() -> Self {
+        Gf256(0)
+    }
+    #[inline]
+    fn one() -> Self {
+        Gf256(1)
+    }
+    #[inline]
+    fn inv(self) -> Self {
+        if self.0 == 0 {
+            panic!("inverse of zero in Gf256");
+        }
+        Gf256(EXP[255 - LOG[self.0 as usize] as usize])
+    }
+    #[inline]
+    fn characteristic() -> u64 {
+        2
+    }
+    #[inline]
+    fn order() -> Option<u64> {
+        Some(256)
+    }
+}
+
+impl FiniteField for Gf256 {
+    #[inline]
+    fn nth_element(i: u64) -> Self {
+        Gf256(i as u8)
+    }
+    #[inline]
+    fn pow(self, e: u64) -> Self {
+        match self.log() {
+            Some(l) => Gf256::exp(l as u64 * (e % 255)),
+            // 0^0 = 1
+            None if e == 0 => Gf256(1),
+            None => Gf256(0),
+        }
+    }
+}
+
+impl From<u8> for Gf256 {
+    #[inline]
+    fn from(x: u8) -> Self {
+        Gf256(x)
+    }
+}
+
+impl From<Gf256> for u8 {
+    #[inline]
+    fn from(x: Gf256) -> Self {
+        x.0
+    }
+}
+
+impl fmt::Display for Gf256 {
+    /// Shows the byte in hexadecimal, as in `0x1d`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#04x}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number_theory::extension_field::{Fpk, IrreducibleModulus};
+
+    /// Carry-less multiplication, then reduction modulo `0x11D`, one bit at a time
+    fn slow_mul(a: u8, b: u8) -> u8 {
+        let mut product: u16 = 0;
+        for i in 0..8 {
+            if b >> i & 1 == 1 {
+                product ^= (a as u16) << i;
+            }
+        }
+        for i in (8..16).rev() {
+            if product >> i & 1 == 1 {
+                product ^= POLY << (i - 8);
+            }
+        }
+        product as u8
+    }
+
+    struct ReedSolomon;
+    impl IrreducibleModulus<2, 8> for ReedSolomon {
+        const COEFFICIENTS: [u64; 8] = [1, 0, 1, 1, 1, 0, 0, 0];
+    }
+
+    type Slow = Fpk<2, 8, ReedSolomon>;
+
+    fn to_fpk(x: Gf256) -> Slow {
+        Slow::new(std::array::from_fn(|i| (x.0 >> i & 1) as u64))
+    }
+
+    #[test]
+    fn tables_agree_with_polynomial_arithmetic() {
+        assert_eq!(LOG[1], 0);
+        assert_eq!(Gf256::exp(8), Gf256(0x1D));
+        for a in 0..=255 {
+            let x = Gf256(a);
+            for b in 0..=255 {
+                let y = Gf256(b);
+                assert_eq!(x * y, Gf256(slow_mul(a, b)));
+                if b != 0 {
+                    assert_eq!(x / y * y, x);
+                }
+            }
+            assert_eq!(x + x, Gf256::zero());
+            assert_eq!(-x, x);
+            if a != 0 {
+                assert_eq!(x * x.inv(), Gf256::one());
+                assert_eq!(Gf256::exp(x.log().unwrap() as u64), x);
+            }
+        }
+
+        // The same field as an extension of GF(2)
+        for a in (0..=255).step_by(7) {
+            for b in (0..=255).step_by(3) {
+                let (x, y) = (Gf256(a), Gf256(b));
+                assert_eq!(to_fpk(x * y), to_fpk(x) * to_fpk(y));
+                if a != 0 {
+                    assert_eq!(to_fpk(x.inv()), to_fpk(x).inv());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn finite_field_methods() {
+        assert_eq!(Gf256::generator(), Gf256::GENERATOR);
+        assert_eq!(Gf256::zero().pow(0), Gf256::one());
+        assert_eq!(Gf256::zero().pow(5), Gf256::zero());
+        for a in 0..=255 {
+            let x = Gf256(a);
+            assert_eq!(x.pow(256), x);
+            assert_eq!(x.pow(3), x * x * x);
+            // Squaring is a bijection in characteristic 2
+            let root = x.sqrt().unwrap();
+            assert_eq!(root * root, x);
+        }
+        assert_eq!(Gf256(0xAB).to_string(), "0xab");
+        assert_eq!(u8::from(Gf256::from(7)), 7);
+    }
+
+    /// The value at `at` of the polynomial of degree below `points.len()` through `points`
+    fn interpolate<F: Field>(points: &[(F, F)], at: F) -> F {
+        let mut sum = F::zero();
+        for (i, &(xi, yi)) in points.iter().enumerate() {
+            let mut term = yi;
+            for (j, &(xj, _)) in points.iter().enumerate() {
+                if i != j {
+                    term = term * (at - xj) / (xi - xj);
+                }
+            }
+            sum = sum + term;
+        }
+        sum
+    }
+
+    #[test]
+    fn erasure_recovery() {
+        // Four data bytes are the values at 0..4 of a cubic; two parity bytes extend it to
+        // 4 and 5. Any four of the six bytes recover the data.
+        let data = b"RAID".map(Gf256);
+        let points: Vec<_> = (0..4).map(|i| (Gf256(i), data[i as usize])).collect();
+        let shard: Vec<_> = (0..6)
+            .map(|i| (Gf256(i), interpolate(&points, Gf256(i))))
+            .collect();
+        assert_eq!(shard[..4], points[..]);
+
+        let survivors = [shard[1], shard[3], shard[4], shard[5]];
+        let recovered: Vec<u8> = (0..4)
+            .map(|i| interpolate(&survivors, Gf256(i)).0)
+            .collect();
+        assert_eq!(recovered, b"RAID");
+    }
+
+    #[test]
+    #[should_panic(expected = "division by zero in Gf256")]
+    fn division_by_zero_panics() {
+        let _ = Gf256(3) / Gf256(0);
+    }
+}
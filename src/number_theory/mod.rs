@@ -1 +1,9 @@
+pub mod big_int;
+pub mod extension_field;
 pub mod field;
+pub mod gf256;
+pub mod mod_int;
+pub mod montgomery;
+pub mod rational;
+pub mod real;
+pub mod zn;
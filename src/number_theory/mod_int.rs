@@ -0,0 +1,473 @@
+/*!
+Number Theory
+
+Runtime-Modulus Modular Integers
+
+Defines:
+- struct [Barrett], a modulus with a precomputed reciprocal
+- trait [Modulus], a marker type naming a modulus set at runtime, and [PrimeModulus]
+- struct [ModInt], the integers modulo a [Modulus]
+- macro [define_modulus](crate::define_modulus)
+
+Implements [Field] and [FiniteField] for:
+- [ModInt] over a [PrimeModulus]
+
+[Fp](super::field::Fp) needs its modulus at compile time. Here the modulus lives in a static
+belonging to a marker type, set once from configuration or user input, so that [ModInt] keeps
+a `Copy` single-word representation and can implement [Field], whose constructors take no
+context.
+*/
+
+use crate::number_theory::field::{Field, FiniteField, add_mod, is_prime_u64, sub_mod};
+#[cfg(any(debug_assertions, feature = "checked"))]
+use contracts::invariant;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::sync::OnceLock;
+
+/// A modulus `m >= 1` with `floor((2^128 - 1) / m)`, which replaces division by `m` with
+/// multiplications (Barrett reduction)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Barrett {
+    m: u64,
+    reciprocal: u128,
+}
+
+/// The high 128 bits of the 256-bit product `a * b`
+#[inline]
+fn mul_high(a: u128, b: u128) -> u128 {
+    let (a0, a1) = (a as u64 as u128, a >> 64);
+    let (b0, b1) = (b as u64 as u128, b >> 64);
+    let (cross0, cross1) = (a1 * b0, a0 * b1);
+    let middle = ((a0 * b0) >> 64) + (cross0 as u64 as u128) + (cross1 as u64 as u128);
+    a1 * b1 + (cross0 >> 64) + (cross1 >> 64) + (middle >> 64)
+}
+
+impl Barrett {
+    /// Precomputes the reciprocal of `m`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(m >= 1)
+    )]
+    pub fn new(m: u64) -> Self {
+        Barrett {
+            m,
+            reciprocal: u128::MAX / m as u128,
+        }
+    }
+
+    /// The modulus
+    #[inline]
+    pub fn modulus(&self) -> u64 {
+        self.m
+    }
+
+    /// `x mod m`, for `x < m^2` or `x < 2^64`
+    #[inline]
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(ret as u128 == x % self.m as u128)
+    )]
+    pub fn reduce(&self, x: u128) -> u64 {
+        // The estimated quotient is at most 2 below the true one
+        let mut r = if x >> 64 == 0 {
+            let x = x as u64;
+            let q = ((x as u128 * (self.reciprocal >> 64)) >> 64) as u64;
+            (x - q.wrapping_mul(self.m)) as u128
+        } else {
+            let q = mul_high(x, self.reciprocal);
+            x - q * self.m as u128
+        };
+        while r >= self.m as u128 {
+            r -= self.m as u128;
+        }
+        r as u64
+    }
+
+    /// `a * b mod m`, for `a, b < m`
+    #[inline]
+    pub fn mul(&self, a: u64, b: u64) -> u64 {
+        self.reduce(a as u128 * b as u128)
+    }
+}
+
+/// Why a modulus could not be set
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ModulusError {
+    /// The modulus must be at least 1
+    Zero,
+    /// A [PrimeModulus] was given a composite number
+    NotPrime(u64),
+    /// The modulus was already set to this different value
+    AlreadySet(u64),
+}
+
+impl fmt::Display for ModulusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModulusError::Zero => write!(f, "the modulus must be at least 1"),
+            ModulusError::NotPrime(m) => write!(f, "the modulus {m} is not prime"),
+            ModulusError::AlreadySet(m) => write!(f, "the modulus was already set to {m}"),
+        }
+    }
+}
+
+impl std::error::Error for ModulusError {}
+
+/**
+A marker type naming a modulus that is set once at runtime, usually declared with
+[define_modulus](crate::define_modulus). Using a [ModInt] over it before the modulus is set
+panics.
+*/
+pub trait Modulus: 'static {
+    /// Whether [set](Modulus::set) only accepts primes
+    const PRIME: bool;
+
+    /// The static holding the modulus, only used by the provided methods
+    fn storage() -> &'static OnceLock<Barrett>;
+
+    /// Sets the modulus to `m`. Setting it again to the same value does nothing.
+    fn set(m: u64) -> Result<(), ModulusError> {
+        if m == 0 {
+            return Err(ModulusError::Zero);
+        }
+        if Self::PRIME && !is_prime_u64(m) {
+            return Err(ModulusError::NotPrime(m));
+        }
+        let current = Self::storage().get_or_init(|| Barrett::new(m)).modulus();
+        if current == m {
+            Ok(())
+        } else {
+            Err(ModulusError::AlreadySet(current))
+        }
+    }
+
+    /// The modulus and its reciprocal; panics if the modulus is not set
+    #[inline]
+    fn barrett() -> &'static Barrett {
+        Self::storage().get().expect("the modulus is not set")
+    }
+}
+
+/**
+A [Modulus] that only accepts primes, over which [ModInt] is a [Field]
+
+It is meant to be implemented by [define_modulus](crate::define_modulus) only, along with
+`PRIME = true`, and requires a supertrait that the macro implements for `: prime` moduli:
+```compile_fail
+use safe_dsa::define_modulus;
+use safe_dsa::number_theory::mod_int::PrimeModulus;
+
+define_modulus!(Composite);
+impl PrimeModulus for Composite {}
+```
+The seal is a convention rather than a guarantee, since the macro needs the supertrait to be
+public: a hand-written impl can still provide it. Division and [inv](Field::inv) therefore
+reject `PRIME = false` at compile time, and with `PRIME = true` the provided
+[set](Modulus::set) only accepts primes.
+```compile_fail
+# use safe_dsa::define_modulus;
+# use safe_dsa::number_theory::field::Field;
+# use safe_dsa::number_theory::mod_int::{ModInt, PrimeModulus, sealed};
+define_modulus!(Composite);
+impl sealed::Prime for Composite {}
+impl PrimeModulus for Composite {}
+
+let x = ModInt::<Composite>::new(5).inv();
+```
+*/
+pub trait PrimeModulus: Modulus + sealed::Prime {}
+
+/// Not part of the API: lets [define_modulus](crate::define_modulus) seal [PrimeModulus]
+#[doc(hidden)]
+pub mod sealed {
+    pub trait Prime {}
+}
+
+/**
+Declares a marker type implementing [Modulus](crate::number_theory::mod_int::Modulus), or
+also [PrimeModulus](crate::number_theory::mod_int::PrimeModulus) when followed by `: prime`.
+
+```
+use safe_dsa::define_modulus;
+use safe_dsa::number_theory::mod_int::{ModInt, Modulus};
+
+define_modulus!(pub Hash: prime);
+
+Hash::set(1_000_000_007).unwrap();
+let x = ModInt::<Hash>::new(1_000_000_000) * ModInt::new(10);
+assert_eq!(x.value(), 999_999_937);
+```
+*/
+#[macro_export]
+macro_rules! define_modulus {
+    ($(#[$attr:meta])* $vis:vis $name:ident) => {
+        $crate::define_modulus!(@define $(#[$attr])* $vis $name, false);
+    };
+    ($(#[$attr:meta])* $vis:vis $name:ident: prime) => {
+        $crate::define_modulus!(@define $(#[$attr])* $vis $name, true);
+        impl $crate::number_theory::mod_int::sealed::Prime for $name {}
+        impl $crate::number_theory::mod_int::PrimeModulus for $name {}
+    };
+    (@define $(#[$attr:meta])* $vis:vis $name:ident, $prime:expr) => {
+        $(#[$attr])*
+        #[derive(Copy, Clone, Debug)]
+        $vis struct $name;
+
+        impl $crate::number_theory::mod_int::Modulus for $name {
+            const PRIME: bool = $prime;
+
+            fn storage()
+                -> &'static ::std::sync::OnceLock<$crate::number_theory::mod_int::Barrett>
+            {
+                static STORAGE: ::std::sync::OnceLock<$crate::number_theory::mod_int::Barrett> =
+                    ::std::sync::OnceLock::new();
+                &STORAGE
+            }
+        }
+    };
+}
+
+/**
+Integers modulo a runtime modulus
+
+The modulus is named by the marker type `M`; see [define_modulus](crate::define_modulus).
+Multiplication uses Barrett reduction.
+*/
+pub struct ModInt<M>(u64, PhantomData<M>);
+
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::invariant(self.0 < M::barrett().modulus())
+)]
+impl<M: Modulus> ModInt<M> {
+    #[inline]
+    pub fn new(x: u64) -> Self {
+        ModInt(M::barrett().reduce(x as u128), PhantomData)
+    }
+
+    #[inline]
+    pub fn value(self) -> u64 {
+        self.0
+    }
+
+    /// The modulus
+    #[inline]
+    pub fn modulus() -> u64 {
+        M::barrett().modulus()
+    }
+
+    /// `self^e`
+    ///
+    /// Time Complexity: `O(log e)`
+    #[inline]
+    pub fn pow(self, mut e: u64) -> Self {
+        let mut base = self;
+        let mut acc = ModInt::new(1);
+        while e > 0 {
+            if e & 1 == 1 {
+                acc = acc * base;
+            }
+            base = base * base;
+            e >>= 1;
+        }
+        acc
+    }
+}
+
+impl<M> Copy for ModInt<M> {}
+
+impl<M> Clone for ModInt<M> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M> PartialEq for ModInt<M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<M> Eq for ModInt<M> {}
+
+impl<M> fmt::Debug for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ModInt").field(&self.0).finish()
+    }
+}
+
+impl<M: Modulus> Add for ModInt<M> {
+    type Output = Self;
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        ModInt(add_mod(self.0, rhs.0, Self::modulus()), PhantomData)
+    }
+}
+impl<M: Modulus> Sub for ModInt<M> {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        ModInt(sub_mod(self.0, rhs.0, Self::modulus()), PhantomData)
+    }
+}
+impl<M: Modulus> Mul for ModInt<M> {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: Self) -> Self::Output {
+        ModInt(M::barrett().mul(self.0, rhs.0), PhantomData)
+    }
+}
+impl<M: Modulus> Neg for ModInt<M> {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self::Output {
+        ModInt(sub_mod(0, self.0, Self::modulus()), PhantomData)
+    }
+}
+impl<M: PrimeModulus> Div for ModInt<M> {
+    type Output = Self;
+    #[inline]
+    fn div(self, rhs: Self) -> Self::Output {
+        const { assert!(M::PRIME, "a PrimeModulus must only accept primes") };
+        if rhs.0 == 0 {
+            panic!("division by zero in ModInt");
+        }
+        // Fermat's Little Theorem
+        self * rhs.pow(Self::modulus() - 2)
+    }
+}
+
+impl<M: PrimeModulus> Field for ModInt<M> {
+    #[inline]
+    fn zero() -> Self {
+        ModInt::new(0)
+    }
+    #[inline]
+    fn one() -> Self {
+        ModInt::new(1)
+    }
+    #[inline]
+    fn inv(self) -> Self {
+        const { assert!(M::PRIME, "a PrimeModulus must only accept primes") };
+        if self.0 == 0 {
+            panic!("inverse of zero in ModInt");
+        }
+        self.pow(Self::modulus() - 2)
+    }
+    #[inline]
+    fn characteristic() -> u64 {
+        Self::modulus()
+    }
+    #[inline]
+    fn order() -> Option<u64> {
+        Some(Self::modulus())
+    }
+}
+
+impl<M: PrimeModulus> FiniteField for ModInt<M> {
+    #[inline]
+    fn nth_element(i: u64) -> Self {
+        ModInt::new(i)
+    }
+    #[inline]
+    fn pow(self, e: u64) -> Self {
+        ModInt::pow(self, e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number_theory::field::Fp;
+    use crate::sorting::prop_test::Rng;
+
+    define_modulus!(Small: prime);
+    define_modulus!(Mersenne61: prime);
+    define_modulus!(Twelve);
+    define_modulus!(Unset);
+
+    #[test]
+    fn barrett_agrees_with_remainder() {
+        let mut rng = Rng::new(44);
+        for m in [
+            1,
+            2,
+            3,
+            7,
+            1 << 32,
+            (1 << 32) + 1,
+            1 << 63,
+            u64::MAX - 1,
+            u64::MAX,
+        ] {
+            let barrett = Barrett::new(m);
+            for _ in 0..200 {
+                let (a, b) = (rng.next_u64() % m, rng.next_u64() % m);
+                assert_eq!(barrett.mul(a, b) as u128, a as u128 * b as u128 % m as u128);
+                let x = rng.next_u64();
+                assert_eq!(barrett.reduce(x as u128), x % m);
+            }
+            let top = m - 1;
+            assert_eq!(
+                barrett.mul(top, top) as u128,
+                top as u128 * top as u128 % m as u128
+            );
+        }
+    }
+
+    #[test]
+    fn mod_int_agrees_with_fp() {
+        const P: u64 = 998_244_353;
+        Small::set(P).unwrap();
+        let mut rng = Rng::new(0x44);
+        for _ in 0..500 {
+            let (x, y) = (rng.next_u64(), rng.next_u64());
+            let (a, b) = (ModInt::<Small>::new(x), ModInt::<Small>::new(y));
+            let (fa, fb) = (Fp::<P>::new(x), Fp::<P>::new(y));
+            assert_eq!((a + b).value(), (fa + fb).value());
+            assert_eq!((a - b).value(), (fa - fb).value());
+            assert_eq!((a * b).value(), (fa * fb).value());
+            assert_eq!((-a).value(), (-fa).value());
+            if !fb.is_zero() {
+                assert_eq!((a / b).value(), (fa / fb).value());
+            }
+        }
+    }
+
+    #[test]
+    fn field_over_mersenne_prime() {
+        Mersenne61::set((1 << 61) - 1).unwrap();
+        type M = ModInt<Mersenne61>;
+        let a = M::new(1 << 62);
+        assert_eq!(a.value(), 2);
+        assert_eq!(a * a.inv(), M::one());
+        assert_eq!(a.powi(61), M::one());
+        assert_eq!(M::characteristic(), (1 << 61) - 1);
+        // 37 is the smallest primitive root modulo 2^61 - 1
+        assert_eq!(M::generator().value(), 37);
+        let root = M::new(5).sqrt().unwrap();
+        assert_eq!(root * root, M::new(5));
+    }
+
+    #[test]
+    fn setting_the_modulus() {
+        assert_eq!(Twelve::set(0), Err(ModulusError::Zero));
+        assert_eq!(Twelve::set(12), Ok(()));
+        assert_eq!(Twelve::set(12), Ok(()));
+        assert_eq!(Twelve::set(13), Err(ModulusError::AlreadySet(12)));
+        assert_eq!(Small::set(12), Err(ModulusError::NotPrime(12)));
+
+        let a = ModInt::<Twelve>::new(7);
+        assert_eq!((a * a).value(), 1);
+        assert_eq!(a.pow(3).value(), 7);
+        assert_eq!(ModInt::<Twelve>::modulus(), 12);
+    }
+
+    #[test]
+    #[should_panic(expected = "the modulus is not set")]
+    fn unset_modulus_panics() {
+        ModInt::<Unset>::new(1);
+    }
+}
@@ -0,0 +1,262 @@
+/*!
+Number Theory
+
+Montgomery Form
+
+Defines:
+- struct [MontgomeryFp]
+
+Implements [Field] and [FiniteField] for:
+- [MontgomeryFp]
+
+An element `x` of the integers modulo an odd prime `P` is stored as `x·R mod P` with
+`R = 2^64`. The product of two stored values is then reduced by Montgomery's REDC, which
+divides by `R` with a multiplication and a shift instead of a division by `P`.
+*/
+
+use crate::number_theory::field::{Field, FiniteField, Fp, add_mod, is_prime_u64, sub_mod};
+#[cfg(any(debug_assertions, feature = "checked"))]
+use contracts::invariant;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/**
+Integers modulo odd prime P, in Montgomery form
+
+Interchangeable with [Fp] for the same `P`, with faster multiplication and conversions to and
+from the canonical value at the boundaries, through [new](MontgomeryFp::new),
+[value](MontgomeryFp::value) and [From]. `P` must be an odd prime, which is checked at
+compile time when `MontgomeryFp<P>` is used.
+
+The gain is for `P > 2^32`, where [Fp] divides a `u128`; below, the compiler already turns
+[Fp]'s division by the constant `P` into multiplications (see `bin/field_bench.rs`).
+*/
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct MontgomeryFp<const P: u64>(u64);
+
+impl<const P: u64> MontgomeryFp<P> {
+    /// `P`, rejected at compile time unless it is an odd prime. Every operation goes through it.
+    pub const MODULUS: u64 = {
+        assert!(
+            is_prime_u64(P) && P != 2,
+            "the modulus of MontgomeryFp<P> must be an odd prime"
+        );
+        P
+    };
+
+    /// `P^-1 mod 2^64`, by Newton's iteration: each step doubles the number of correct low
+    /// bits, starting from the 3 that `P·P ≡ 1 (mod 8)` gives for any odd `P`
+    const P_INV: u64 = {
+        let p = Self::MODULUS;
+        let mut inv = p;
+        let mut i = 0;
+        while i < 5 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(p.wrapping_mul(inv)));
+            i += 1;
+        }
+        assert!(p.wrapping_mul(inv) == 1);
+        inv
+    };
+
+    /// `R^2 mod P`, which converts to Montgomery form through [reduce](Self::reduce)
+    const R2: u64 = {
+        let r = (1u128 << 64) % Self::MODULUS as u128;
+        (r * r % Self::MODULUS as u128) as u64
+    };
+
+    /// Montgomery's REDC: `t·R^-1 mod P`, for `t < P·R`
+    #[inline]
+    const fn reduce(t: u128) -> u64 {
+        // m·P agrees with t on the low 64 bits, so t - m·P is exactly divisible by R
+        let m = (t as u64).wrapping_mul(Self::P_INV);
+        let mp = ((m as u128 * Self::MODULUS as u128) >> 64) as u64;
+        let (u, borrow) = ((t >> 64) as u64).overflowing_sub(mp);
+        if borrow {
+            u.wrapping_add(Self::MODULUS)
+        } else {
+            u
+        }
+    }
+}
+
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::invariant(self.0 < P)
+)]
+impl<const P: u64> MontgomeryFp<P> {
+    /// Converts `x mod P` to Montgomery form
+    #[inline]
+    pub fn new(x: u64) -> Self {
+        let x = x % Self::MODULUS;
+        MontgomeryFp::<P>(Self::reduce(x as u128 * Self::R2 as u128))
+    }
+
+    /// The canonical value, in `0..P`
+    #[inline]
+    pub fn value(self) -> u64 {
+        Self::reduce(self.0 as u128)
+    }
+
+    /// `self^e`, for exponents that may not fit in [Field::powi]
+    #[inline]
+    fn pow_raw(self, mut e: u64) -> Self {
+        let mut base = self;
+        let mut acc = Self::one();
+        while e > 0 {
+            if e & 1 == 1 {
+                acc = acc * base;
+            }
+            base = base * base;
+            e >>= 1;
+        }
+        acc
+    }
+}
+
+impl<const P: u64> Add for MontgomeryFp<P> {
+    type Output = Self;
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        MontgomeryFp::<P>(add_mod(self.0, rhs.0, Self::MODULUS))
+    }
+}
+impl<const P: u64> Sub for MontgomeryFp<P> {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        MontgomeryFp::<P>(sub_mod(self.0, rhs.0, Self::MODULUS))
+    }
+}
+impl<const P: u64> Mul for MontgomeryFp<P> {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: Self) -> Self::Output {
+        // (aR)(bR)R^-1 = (ab)R
+        MontgomeryFp::<P>(Self::reduce(self.0 as u128 * rhs.0 as u128))
+    }
+}
+impl<const P: u64> Div for MontgomeryFp<P> {
+    type Output = Self;
+    #[inline]
+    fn div(self, rhs: Self) -> Self::Output {
+        if rhs.0 == 0 {
+            panic!("division by zero in MontgomeryFp");
+        }
+        // Fermat's Little Theorem
+        self * rhs.pow_raw(Self::MODULUS - 2)
+    }
+}
+impl<const P: u64> Neg for MontgomeryFp<P> {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self::Output {
+        MontgomeryFp::<P>(sub_mod(0, self.0, Self::MODULUS))
+    }
+}
+
+impl<const P: u64> Field for MontgomeryFp<P> {
+    #[inline]
+    fn zero() -> Self {
+        MontgomeryFp::<P>::new(0)
+    }
+    #[inline]
+    fn one() -> Self {
+        MontgomeryFp::<P>::new(1)
+    }
+    #[inline]
+    fn inv(self) -> Self {
+        if self.0 == 0 {
+            panic!("inverse of zero in MontgomeryFp");
+        }
+        self.pow_raw(Self::MODULUS - 2)
+    }
+    #[inline]
+    fn characteristic() -> u64 {
+        Self::MODULUS
+    }
+    #[inline]
+    fn order() -> Option<u64> {
+        Some(Self::MODULUS)
+    }
+}
+
+impl<const P: u64> FiniteField for MontgomeryFp<P> {
+    #[inline]
+    fn nth_element(i: u64) -> Self {
+        MontgomeryFp::<P>::new(i)
+    }
+    #[inline]
+    fn pow(self, e: u64) -> Self {
+        self.pow_raw(e)
+    }
+}
+
+impl<const P: u64> From<Fp<P>> for MontgomeryFp<P> {
+    #[inline]
+    fn from(x: Fp<P>) -> Self {
+        MontgomeryFp::<P>::new(x.value())
+    }
+}
+
+impl<const P: u64> From<MontgomeryFp<P>> for Fp<P> {
+    #[inline]
+    fn from(x: MontgomeryFp<P>) -> Self {
+        Fp::<P>::new(x.value())
+    }
+}
+
+impl<const P: u64> fmt::Debug for MontgomeryFp<P> {
+    /// Shows the canonical value
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("MontgomeryFp").field(&self.value()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks every operation against [Fp] on the same values
+    fn agrees_with_fp<const P: u64>(values: &[u64]) {
+        for &x in values {
+            let (a, fa) = (MontgomeryFp::<P>::new(x), Fp::<P>::new(x));
+            assert_eq!(a.value(), fa.value());
+            assert_eq!((-a).value(), (-fa).value());
+            for &y in values {
+                let (b, fb) = (MontgomeryFp::<P>::new(y), Fp::<P>::new(y));
+                assert_eq!((a + b).value(), (fa + fb).value());
+                assert_eq!((a - b).value(), (fa - fb).value());
+                assert_eq!((a * b).value(), (fa * fb).value());
+                if !fb.is_zero() {
+                    assert_eq!((a / b).value(), (fa / fb).value());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn montgomery_agrees_with_fp() {
+        let values = [0, 1, 2, 3, 12345, 1 << 32, (1 << 61) - 2, 1 << 63, u64::MAX];
+        agrees_with_fp::<3>(&values);
+        agrees_with_fp::<998_244_353>(&values);
+        agrees_with_fp::<{ (1 << 61) - 1 }>(&values);
+        agrees_with_fp::<18_446_744_073_709_551_557>(&values);
+    }
+
+    #[test]
+    fn field_identities() {
+        type M = MontgomeryFp<1_000_000_007>;
+        let a = M::new(123_456_789);
+        assert_eq!(a * a.inv(), M::one());
+        assert_eq!(a.powi(-3) * a.powi(3), M::one());
+        assert_eq!(a + M::zero(), a);
+        assert_eq!(Fp::from(a), Fp::new(123_456_789));
+        assert_eq!(M::from(Fp::new(5)).value(), 5);
+        assert_eq!(format!("{a:?}"), "MontgomeryFp(123456789)");
+        assert_eq!(M::generator().value(), 5);
+        assert_eq!(
+            a.legendre(),
+            Fp::<1_000_000_007>::new(123_456_789).legendre()
+        );
+    }
+}
@@ -0,0 +1,502 @@
+/*!
+Number Theory
+
+Rational Numbers
+
+Defines:
+- struct [Rational], an exact fraction of two [i64]s
+- enum [ParseRationalError]
+
+Implements [Field] for:
+- [Rational]
+
+Unlike [f64], a [Rational] is exact, so code generic over [Field] gives exact answers.
+Numerators and denominators are [i64]; operations compute in [i128], which cannot overflow,
+and then reduce by the gcd. A result that still does not fit in [i64] is
+[FieldError::Overflow] from the `checked_*` methods, and a panic from the operators.
+*/
+
+use crate::number_theory::field::{Field, FieldError};
+#[cfg(any(debug_assertions, feature = "checked"))]
+use contracts::invariant;
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::num::ParseIntError;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+
+/// Greatest common divisor
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/**
+An exact rational number `num / den`
+
+Always normalized: `den > 0` and `gcd(num, den) = 1`, with zero as `0/1`. The derived
+equality and hash are therefore those of the number; [Ord] compares the numbers.
+*/
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i64,
+    den: i64,
+}
+
+impl Rational {
+    /// `num / den`, or [FieldError::DivisionByZero] if `den` is zero, or
+    /// [FieldError::Overflow] if the normalized fraction does not fit, like `1 / i64::MIN`
+    #[inline]
+    pub fn new(num: i64, den: i64) -> Result<Self, FieldError> {
+        Rational::from_i128(num as i128, den as i128)
+    }
+
+    /// Normalizes `num / den`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(ret.iter().all(|r| r.is_normalized()))
+    )]
+    fn from_i128(num: i128, den: i128) -> Result<Self, FieldError> {
+        if den == 0 {
+            return Err(FieldError::DivisionByZero);
+        }
+        let g = gcd(num.unsigned_abs(), den.unsigned_abs());
+        let (n, d) = (num.unsigned_abs() / g, den.unsigned_abs() / g);
+        let negative = (num < 0) != (den < 0);
+        let num = i128::try_from(n)
+            .ok()
+            .map(|n| if negative { -n } else { n })
+            .and_then(|n| i64::try_from(n).ok());
+        match (num, i64::try_from(d)) {
+            (Some(num), Ok(den)) => Ok(Rational { num, den }),
+            _ => Err(FieldError::Overflow),
+        }
+    }
+
+    /// Unwraps a `checked_*` result for the operators
+    #[inline]
+    fn expect(result: Result<Self, FieldError>) -> Self {
+        result.unwrap_or_else(|e| panic!("{e} in Rational"))
+    }
+
+    /// The invariant: `den > 0` and `gcd(num, den) = 1`
+    #[cfg(any(debug_assertions, feature = "checked"))]
+    fn is_normalized(&self) -> bool {
+        self.den > 0 && gcd(self.num.unsigned_abs() as u128, self.den as u128) == 1
+    }
+}
+
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::invariant(self.is_normalized())
+)]
+impl Rational {
+    /// The numerator, which carries the sign
+    #[inline]
+    pub fn numer(self) -> i64 {
+        self.num
+    }
+
+    /// The denominator, which is positive
+    #[inline]
+    pub fn denom(self) -> i64 {
+        self.den
+    }
+
+    #[inline]
+    pub fn is_integer(self) -> bool {
+        self.den == 1
+    }
+
+    /// `self + rhs`, or [FieldError::Overflow]
+    #[inline]
+    pub fn checked_add(self, rhs: Self) -> Result<Self, FieldError> {
+        let (a, b, c, d) = self.wide(rhs);
+        Rational::from_i128(a * d + c * b, b * d)
+    }
+
+    /// `self - rhs`, or [FieldError::Overflow]
+    #[inline]
+    pub fn checked_sub(self, rhs: Self) -> Result<Self, FieldError> {
+        let (a, b, c, d) = self.wide(rhs);
+        Rational::from_i128(a * d - c * b, b * d)
+    }
+
+    /// `self * rhs`, or [FieldError::Overflow]
+    #[inline]
+    pub fn checked_mul(self, rhs: Self) -> Result<Self, FieldError> {
+        let (a, b, c, d) = self.wide(rhs);
+        Rational::from_i128(a * c, b * d)
+    }
+
+    /// `-self`, or [FieldError::Overflow] for a numerator of [i64::MIN]
+    #[inline]
+    pub fn checked_neg(self) -> Result<Self, FieldError> {
+        match self.num.checked_neg() {
+            Some(num) => Ok(Rational { num, den: self.den }),
+            None => Err(FieldError::Overflow),
+        }
+    }
+
+    /// Both fractions in [i128], where products of two parts cannot overflow
+    #[inline]
+    fn wide(self, rhs: Self) -> (i128, i128, i128, i128) {
+        (
+            self.num as i128,
+            self.den as i128,
+            rhs.num as i128,
+            rhs.den as i128,
+        )
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        Rational::expect(self.checked_add(rhs))
+    }
+}
+impl Sub for Rational {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        Rational::expect(self.checked_sub(rhs))
+    }
+}
+impl Mul for Rational {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: Self) -> Self::Output {
+        Rational::expect(self.checked_mul(rhs))
+    }
+}
+impl Div for Rational {
+    type Output = Self;
+    #[inline]
+    fn div(self, rhs: Self) -> Self::Output {
+        Rational::expect(self.checked_div(rhs))
+    }
+}
+impl Neg for Rational {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self::Output {
+        Rational::expect(self.checked_neg())
+    }
+}
+
+impl Field for Rational {
+    #[inline]
+    fn zero() -> Self {
+        Rational { num: 0, den: 1 }
+    }
+    #[inline]
+    fn one() -> Self {
+        Rational { num: 1, den: 1 }
+    }
+    #[inline]
+    fn inv(self) -> Self {
+        Rational::expect(self.checked_inv())
+    }
+    #[inline]
+    fn characteristic() -> u64 {
+        0
+    }
+    #[inline]
+    fn order() -> Option<u64> {
+        None
+    }
+
+    /// `1 / self`, or [FieldError::DivisionByZero], or [FieldError::Overflow] for a numerator
+    /// of [i64::MIN]
+    #[inline]
+    fn checked_inv(self) -> Result<Self, FieldError> {
+        Rational::from_i128(self.den as i128, self.num as i128)
+    }
+
+    /// `self / rhs`, or [FieldError::DivisionByZero], or [FieldError::Overflow]
+    #[inline]
+    fn checked_div(self, rhs: Self) -> Result<Self, FieldError> {
+        let (a, b, c, d) = self.wide(rhs);
+        Rational::from_i128(a * d, b * c)
+    }
+
+    /// `self^n`, or [FieldError::DivisionByZero], or [FieldError::Overflow]
+    ///
+    /// Time Complexity: `O(log n)`
+    fn checked_powi(self, n: i64) -> Result<Self, FieldError> {
+        let mut base = if n < 0 { self.checked_inv()? } else { self };
+        let mut e = n.unsigned_abs();
+        let mut acc = Rational::one();
+        while e > 0 {
+            if e & 1 == 1 {
+                acc = acc.checked_mul(base)?;
+            }
+            e >>= 1;
+            if e > 0 {
+                base = base.checked_mul(base)?;
+            }
+        }
+        Ok(acc)
+    }
+}
+
+impl Default for Rational {
+    /// Zero
+    #[inline]
+    fn default() -> Self {
+        Rational::zero()
+    }
+}
+
+impl From<i64> for Rational {
+    #[inline]
+    fn from(n: i64) -> Self {
+        Rational { num: n, den: 1 }
+    }
+}
+
+impl PartialOrd for Rational {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Denominators are positive, so cross-multiplying keeps the order
+        let (a, b, c, d) = self.wide(*other);
+        (a * d).cmp(&(c * b))
+    }
+}
+
+impl fmt::Display for Rational {
+    /// `num/den`, or just `num` for integers
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+/// Why a string is not a [Rational]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseRationalError {
+    /// Not of the form `a`, `a/b` or `a.b`, with decimal integers `a` and `b`
+    Invalid,
+    /// Well formed, but the denominator is zero or the value does not fit
+    Field(FieldError),
+}
+
+impl fmt::Display for ParseRationalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseRationalError::Invalid => write!(f, "invalid rational number"),
+            ParseRationalError::Field(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl Error for ParseRationalError {}
+
+impl From<ParseIntError> for ParseRationalError {
+    fn from(_: ParseIntError) -> Self {
+        ParseRationalError::Invalid
+    }
+}
+
+impl From<FieldError> for ParseRationalError {
+    fn from(e: FieldError) -> Self {
+        ParseRationalError::Field(e)
+    }
+}
+
+impl FromStr for Rational {
+    type Err = ParseRationalError;
+
+    /// Parses a fraction `a/b`, an integer, or an exact decimal such as `-12.50`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((num, den)) = s.split_once('/') {
+            return Ok(Rational::from_i128(num.parse()?, den.parse()?)?);
+        }
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+        let all_digits = |part: &str| part.bytes().all(|c| c.is_ascii_digit());
+        if int.len() + frac.len() == 0 || !all_digits(int) || !all_digits(frac) {
+            return Err(ParseRationalError::Invalid);
+        }
+        // Every digit of both parts, as one integer over 10^(fractional digits)
+        let num = int.bytes().chain(frac.bytes()).try_fold(0i128, |acc, c| {
+            acc.checked_mul(10)?.checked_add((c - b'0') as i128)
+        });
+        let den = u32::try_from(frac.len())
+            .ok()
+            .and_then(|k| 10i128.checked_pow(k));
+        match (num, den) {
+            (Some(num), Some(den)) => {
+                Ok(Rational::from_i128(if negative { -num } else { num }, den)?)
+            }
+            _ => Err(FieldError::Overflow.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{ENABLED, Kind, catch};
+    use crate::data_structures::fenwick_tree::FenwickTree;
+    use crate::sorting::merge_sort;
+
+    fn q(num: i64, den: i64) -> Rational {
+        Rational::new(num, den).unwrap()
+    }
+
+    #[test]
+    fn arithmetic_is_exact() {
+        assert_eq!(q(1, 2) + q(1, 3), q(5, 6));
+        assert_eq!(q(1, 2) - q(5, 6), q(-1, 3));
+        assert_eq!(q(-4, 6) * q(9, 2), Rational::from(-3));
+        assert_eq!(q(3, 4) / q(-9, 8), q(-2, 3));
+        assert_eq!(q(2, 3).inv(), q(3, 2));
+        assert_eq!(q(-2, 3).powi(-3), q(-27, 8));
+        assert_eq!(q(6, -4), q(-3, 2));
+        assert_eq!((q(0, -5).numer(), q(0, -5).denom()), (0, 1));
+        assert!(q(10, 5).is_integer());
+
+        // Ten tenths are exactly one, unlike in f64
+        let tenths = FenwickTree::from(vec![q(1, 10); 10]);
+        assert_eq!(tenths.prefix_sum(10), Rational::one());
+        let mut values = [q(2, 7), Rational::zero(), q(-5, 3)];
+        Rational::batch_inv(&mut values);
+        assert_eq!(values, [q(7, 2), Rational::zero(), q(-3, 5)]);
+    }
+
+    #[test]
+    fn overflow_is_reported() {
+        let max = Rational::from(i64::MAX);
+        let min = Rational::from(i64::MIN);
+        assert_eq!(max.checked_add(Rational::one()), Err(FieldError::Overflow));
+        assert_eq!(min.checked_neg(), Err(FieldError::Overflow));
+        assert_eq!(min.checked_inv(), Err(FieldError::Overflow));
+        assert_eq!(Rational::new(1, i64::MIN), Err(FieldError::Overflow));
+        assert_eq!(Rational::new(1, 0), Err(FieldError::DivisionByZero));
+        assert_eq!(
+            q(1, 2).checked_div(Rational::zero()),
+            Err(FieldError::DivisionByZero)
+        );
+        assert_eq!(
+            Rational::zero().checked_powi(-1),
+            Err(FieldError::DivisionByZero)
+        );
+        assert_eq!(
+            Rational::from(2).checked_powi(63),
+            Err(FieldError::Overflow)
+        );
+        assert_eq!(q(1, 2).checked_powi(-62), Ok(Rational::from(1 << 62)));
+
+        // Intermediate results beyond i64 are fine as long as the result fits
+        assert_eq!(Rational::new(i64::MIN, 2), Ok(Rational::from(-(1 << 62))));
+        assert_eq!(max * q(1, i64::MAX), Rational::one());
+        assert_eq!(max - max, Rational::zero());
+        assert_eq!(
+            Rational::from(3).powi(39),
+            Rational::from(4_052_555_153_018_976_267)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the result overflows in Rational")]
+    fn operators_panic_on_overflow() {
+        let _ = Rational::from(i64::MAX) * Rational::from(2);
+    }
+
+    #[test]
+    fn parsing_and_display() {
+        let parse = |s: &str| s.parse::<Rational>();
+        assert_eq!(parse("3/4"), Ok(q(3, 4)));
+        assert_eq!(parse("-6/8"), Ok(q(-3, 4)));
+        assert_eq!(parse("6/-8"), Ok(q(-3, 4)));
+        assert_eq!(parse("+42"), Ok(Rational::from(42)));
+        assert_eq!(parse("12.50"), Ok(q(25, 2)));
+        assert_eq!(parse("-0.125"), Ok(q(-1, 8)));
+        assert_eq!(parse(".5"), Ok(q(1, 2)));
+        assert_eq!(parse("7."), Ok(Rational::from(7)));
+        assert_eq!(
+            parse("1/0"),
+            Err(ParseRationalError::Field(FieldError::DivisionByZero))
+        );
+        assert_eq!(
+            parse("9223372036854775808"),
+            Err(ParseRationalError::Field(FieldError::Overflow))
+        );
+        assert_eq!(
+            parse("0.0000000000000000000000000000000000000001"),
+            Err(ParseRationalError::Field(FieldError::Overflow))
+        );
+        for bad in [
+            "", "-", ".", "abc", "1/2/3", "1e3", "1.2.3", "- 1", "1/", "--1",
+        ] {
+            assert_eq!(parse(bad), Err(ParseRationalError::Invalid), "{bad}");
+        }
+
+        for x in [
+            q(-3, 4),
+            Rational::from(12),
+            Rational::zero(),
+            q(1, i64::MAX),
+        ] {
+            assert_eq!(parse(&x.to_string()), Ok(x));
+        }
+        assert_eq!(q(-3, 4).to_string(), "-3/4");
+        assert_eq!(Rational::default().to_string(), "0");
+    }
+
+    #[test]
+    fn order_compares_values() {
+        let mut values = vec![
+            q(1, 2),
+            q(-1, 3),
+            q(2, 3),
+            Rational::zero(),
+            q(-1, 2),
+            q(1, 3),
+        ];
+        merge_sort::sort(&mut values);
+        assert_eq!(
+            values,
+            [
+                q(-1, 2),
+                q(-1, 3),
+                Rational::zero(),
+                q(1, 3),
+                q(1, 2),
+                q(2, 3)
+            ]
+        );
+        assert!(q(i64::MAX - 1, i64::MAX) < q(i64::MAX, i64::MAX - 1));
+        assert!(Rational::from(i64::MIN) < q(-1, i64::MAX));
+    }
+
+    #[test]
+    fn unreduced_fraction_violates_invariant() {
+        let half = Rational { num: 2, den: 4 };
+        let result = catch(move || half.numer());
+        assert_eq!(result.is_err(), ENABLED);
+        if let Err(v) = result {
+            assert_eq!(v.kind, Kind::Invariant);
+            assert_eq!(v.module, "safe_dsa::number_theory::rational");
+        }
+    }
+}
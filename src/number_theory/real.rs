@@ -0,0 +1,296 @@
+/*!
+Number Theory
+
+Real Numbers
+
+Defines:
+- struct [Real], an [f64] that is never NaN
+
+Implements [Field] for:
+- [Real]
+
+NaN is not equal to itself, which breaks the equivalence relation that [Field] expects of
+equality, and it spreads silently through [f64] arithmetic. A [Real] is never NaN: building
+one from NaN fails, and so does every `checked_*` operation whose result would be NaN, such
+as `∞ - ∞`. The operators panic instead. Equality, order and hashing are then total, with
+`-0.0 == 0.0`.
+*/
+
+use crate::number_theory::field::{Field, FieldError};
+#[cfg(any(debug_assertions, feature = "checked"))]
+use contracts::invariant;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// An [f64] that is never NaN, though it may be infinite
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Real(f64);
+
+impl Real {
+    /// `x`, or [FieldError::NotANumber] if it is NaN
+    #[inline]
+    pub fn new(x: f64) -> Result<Self, FieldError> {
+        if x.is_nan() {
+            Err(FieldError::NotANumber)
+        } else {
+            Ok(Real(x))
+        }
+    }
+
+    /// Unwraps a `checked_*` result for the operators
+    #[inline]
+    fn expect(result: Result<Self, FieldError>) -> Self {
+        result.unwrap_or_else(|e| panic!("{e} in Real"))
+    }
+}
+
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::invariant(!self.0.is_nan())
+)]
+impl Real {
+    #[inline]
+    pub fn get(self) -> f64 {
+        self.0
+    }
+
+    /// `self + rhs`, or [FieldError::NotANumber] for `∞ + -∞`
+    #[inline]
+    pub fn checked_add(self, rhs: Self) -> Result<Self, FieldError> {
+        Real::new(self.0 + rhs.0)
+    }
+
+    /// `self - rhs`, or [FieldError::NotANumber] for `∞ - ∞`
+    #[inline]
+    pub fn checked_sub(self, rhs: Self) -> Result<Self, FieldError> {
+        Real::new(self.0 - rhs.0)
+    }
+
+    /// `self * rhs`, or [FieldError::NotANumber] for `0 · ∞`
+    #[inline]
+    pub fn checked_mul(self, rhs: Self) -> Result<Self, FieldError> {
+        Real::new(self.0 * rhs.0)
+    }
+}
+
+impl Add for Real {
+    type Output = Self;
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        Real::expect(self.checked_add(rhs))
+    }
+}
+impl Sub for Real {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        Real::expect(self.checked_sub(rhs))
+    }
+}
+impl Mul for Real {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: Self) -> Self::Output {
+        Real::expect(self.checked_mul(rhs))
+    }
+}
+impl Div for Real {
+    type Output = Self;
+    #[inline]
+    fn div(self, rhs: Self) -> Self::Output {
+        Real::expect(self.checked_div(rhs))
+    }
+}
+impl Neg for Real {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self::Output {
+        Real(-self.0)
+    }
+}
+
+impl Field for Real {
+    #[inline]
+    fn zero() -> Self {
+        Real(0.0)
+    }
+    #[inline]
+    fn one() -> Self {
+        Real(1.0)
+    }
+    #[inline]
+    fn inv(self) -> Self {
+        Real::expect(self.checked_inv())
+    }
+    #[inline]
+    fn characteristic() -> u64 {
+        0
+    }
+    #[inline]
+    fn order() -> Option<u64> {
+        None
+    }
+
+    #[inline]
+    fn try_inv(self) -> Option<Self> {
+        // 1/∞ = 0 is fine; only zero fails
+        (!self.is_zero()).then(|| Real(1.0 / self.0))
+    }
+
+    /// `self / rhs`, or [FieldError::DivisionByZero] if `rhs` is zero, or
+    /// [FieldError::NotANumber] for `∞ / ∞`
+    #[inline]
+    fn checked_div(self, rhs: Self) -> Result<Self, FieldError> {
+        if rhs.is_zero() {
+            Err(FieldError::DivisionByZero)
+        } else {
+            Real::new(self.0 / rhs.0)
+        }
+    }
+
+    /// Inverts each element on its own: the running products of Montgomery's trick overflow
+    /// to infinity and then meet zero, and a division costs about as much as a multiplication.
+    fn batch_inv(values: &mut [Self]) {
+        for x in values.iter_mut() {
+            if let Some(inv) = x.try_inv() {
+                *x = inv;
+            }
+        }
+    }
+}
+
+impl PartialEq for Real {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for Real {}
+
+impl PartialOrd for Real {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Real {
+    /// The order of [f64], which is total without NaN, so `-0.0` and `0.0` are equal
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.partial_cmp(&other.0).expect("a Real is never NaN")
+    }
+}
+
+impl Hash for Real {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // -0.0 + 0.0 = 0.0, so equal values hash alike
+        (self.0 + 0.0).to_bits().hash(state);
+    }
+}
+
+impl fmt::Display for Real {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl TryFrom<f64> for Real {
+    type Error = FieldError;
+
+    #[inline]
+    fn try_from(x: f64) -> Result<Self, Self::Error> {
+        Real::new(x)
+    }
+}
+
+impl From<Real> for f64 {
+    #[inline]
+    fn from(x: Real) -> Self {
+        x.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sorting::heap_sort;
+    use std::collections::HashSet;
+
+    fn real(x: f64) -> Real {
+        Real::new(x).unwrap()
+    }
+
+    #[test]
+    fn nan_is_rejected() {
+        let inf = real(f64::INFINITY);
+        assert_eq!(Real::new(f64::NAN), Err(FieldError::NotANumber));
+        assert_eq!(Real::try_from(-f64::NAN), Err(FieldError::NotANumber));
+        assert_eq!(inf.checked_add(-inf), Err(FieldError::NotANumber));
+        assert_eq!(inf.checked_sub(inf), Err(FieldError::NotANumber));
+        assert_eq!(inf.checked_mul(Real::zero()), Err(FieldError::NotANumber));
+        assert_eq!(inf.checked_div(inf), Err(FieldError::NotANumber));
+        assert_eq!(
+            inf.checked_div(Real::zero()),
+            Err(FieldError::DivisionByZero)
+        );
+        assert_eq!(real(-0.0).checked_inv(), Err(FieldError::DivisionByZero));
+        assert_eq!(
+            Real::zero().checked_powi(-3),
+            Err(FieldError::DivisionByZero)
+        );
+
+        assert_eq!(inf.checked_add(inf), Ok(inf));
+        assert_eq!(inf.inv(), Real::zero());
+        assert_eq!(real(3.0) / real(4.0), real(0.75));
+        assert_eq!(real(2.0).powi(-2), real(0.25));
+        assert_eq!(f64::from(-real(1.5)), -1.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "the result is not a number in Real")]
+    fn operators_panic_on_nan() {
+        let inf = real(f64::INFINITY);
+        let _ = inf - inf;
+    }
+
+    #[test]
+    #[should_panic(expected = "division by zero in Real")]
+    fn division_by_zero_panics() {
+        let _ = Real::one() / Real::zero();
+    }
+
+    #[test]
+    fn total_equality_order_and_hash() {
+        assert_eq!(real(-0.0), real(0.0));
+        let set: HashSet<Real> = [0.0, -0.0, 1.0, 1.0, f64::INFINITY]
+            .into_iter()
+            .map(real)
+            .collect();
+        assert_eq!(set.len(), 3);
+
+        let mut values: Vec<Real> = [2.5, f64::NEG_INFINITY, -1.0, 0.0, f64::INFINITY, -0.0]
+            .into_iter()
+            .map(real)
+            .collect();
+        heap_sort::sort(&mut values);
+        let sorted: Vec<f64> = values.iter().map(|x| x.get()).collect();
+        assert_eq!(sorted[..3], [f64::NEG_INFINITY, -1.0, 0.0]);
+        assert_eq!(sorted[4..], [2.5, f64::INFINITY]);
+        assert_eq!(format!("{}", real(2.5)), "2.5");
+    }
+
+    #[test]
+    fn batch_inversion_does_not_overflow() {
+        let raw = [1e300, 1e300, 0.0, 4.0, 1e-300];
+        let mut values = raw.map(real);
+        Real::batch_inv(&mut values);
+        assert_eq!(
+            values,
+            raw.map(|x| real(if x == 0.0 { 0.0 } else { 1.0 / x }))
+        );
+    }
+}
@@ -0,0 +1,162 @@
+/*!
+Number Theory
+
+Integers modulo N
+
+Defines:
+- struct [Zn], the ring of integers modulo any `N >= 1`
+
+Unlike [Fp](super::field::Fp), `N` need not be prime, so [Zn] is only a ring: an element
+has an inverse exactly when it is coprime to `N`.
+*/
+
+use crate::number_theory::field::{add_mod, mul_mod, pow_mod, sub_mod};
+#[cfg(any(debug_assertions, feature = "checked"))]
+use contracts::invariant;
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// Integers modulo N
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Zn<const N: u64>(u64);
+
+impl<const N: u64> Zn<N> {
+    /// `N`, rejected at compile time if zero. Every operation goes through it.
+    pub const MODULUS: u64 = {
+        assert!(N > 0, "the modulus of Zn<N> must be positive");
+        N
+    };
+}
+
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::invariant(self.0 < N)
+)]
+impl<const N: u64> Zn<N> {
+    #[inline]
+    pub fn new(x: u64) -> Self {
+        Zn::<N>(x % Self::MODULUS)
+    }
+
+    #[inline]
+    pub fn value(self) -> u64 {
+        let _ = Self::MODULUS;
+        self.0
+    }
+
+    /// Additive identity
+    #[inline]
+    pub fn zero() -> Self {
+        Zn::<N>::new(0)
+    }
+
+    /// Multiplicative identity, which is zero when `N = 1`
+    #[inline]
+    pub fn one() -> Self {
+        Zn::<N>::new(1)
+    }
+
+    /// `self^e`
+    ///
+    /// Time Complexity: `O(log e)`
+    #[inline]
+    pub fn pow(self, e: u64) -> Self {
+        Zn::<N>(pow_mod(self.0, e, Self::MODULUS))
+    }
+
+    /// The multiplicative inverse, or `None` if `self` is not coprime to `N`
+    ///
+    /// Time Complexity: `O(log N)`
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::ensures(ret.is_none_or(|inv| self * inv == Self::one()))
+    )]
+    pub fn inv(self) -> Option<Self> {
+        // Extended Euclid on (N, self), tracking the coefficient of self modulo N
+        let (mut r0, mut r1) = (Self::MODULUS, self.0);
+        let (mut t0, mut t1) = (Self::zero(), Self::one());
+        while r1 != 0 {
+            let q = r0 / r1;
+            (r0, r1) = (r1, r0 - q * r1);
+            (t0, t1) = (t1, t0 - Zn::<N>::new(q) * t1);
+        }
+        (r0 == 1).then_some(t0)
+    }
+
+    /// Whether `self` has a multiplicative inverse
+    pub fn is_unit(self) -> bool {
+        self.inv().is_some()
+    }
+}
+
+impl<const N: u64> Add for Zn<N> {
+    type Output = Self;
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        Zn::<N>(add_mod(self.0, rhs.0, Self::MODULUS))
+    }
+}
+impl<const N: u64> Sub for Zn<N> {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        Zn::<N>(sub_mod(self.0, rhs.0, Self::MODULUS))
+    }
+}
+impl<const N: u64> Mul for Zn<N> {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: Self) -> Self::Output {
+        Zn::<N>(mul_mod(self.0, rhs.0, Self::MODULUS))
+    }
+}
+impl<const N: u64> Neg for Zn<N> {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self::Output {
+        Zn::<N>(sub_mod(0, self.0, Self::MODULUS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    type Z12 = Zn<12>;
+
+    #[test]
+    fn zn_basic_ops() {
+        let a = Z12::new(7);
+        let b = Z12::new(9);
+        assert_eq!((a + b).value(), 4);
+        assert_eq!((a - b).value(), 10);
+        assert_eq!((a * b).value(), 3);
+        assert_eq!((-a).value(), 5);
+        assert_eq!(a.pow(2).value(), 1);
+        assert_eq!(Z12::new(100).value(), 4);
+    }
+
+    #[test]
+    fn only_coprime_elements_are_units() {
+        for x in 0..12 {
+            let a = Z12::new(x);
+            let coprime = [1, 5, 7, 11].contains(&x);
+            assert_eq!(a.is_unit(), coprime, "{x}");
+            if let Some(inv) = a.inv() {
+                assert_eq!((a * inv).value(), 1);
+            }
+        }
+        assert_eq!(Zn::<1>::new(5).inv(), Some(Zn::new(0)));
+    }
+
+    #[test]
+    fn zn_large_moduli() {
+        // 2^64 - 1 = 3 * 5 * 17 * 257 * 641 * 65537 * 6700417
+        type Z = Zn<{ u64::MAX }>;
+        let a = Z::new(u64::MAX - 1);
+        assert_eq!((a + a).value(), u64::MAX - 2);
+        assert_eq!((a * a).value(), 1);
+        assert_eq!(a.inv(), Some(a));
+        assert_eq!(Z::new(641).inv(), None);
+        let b = Z::new(1 << 63);
+        assert_eq!(b * b.inv().unwrap(), Z::one());
+    }
+}
@@ -0,0 +1,230 @@
+/*!
+Searching Algorithms
+
+Binary Search
+
+Implements:
+- [lower_bound]
+- [upper_bound]
+- [equal_range]
+- [binary_search]
+- [partition_point]
+
+All midpoints are computed as `lo + (hi - lo) / 2`, which cannot overflow.
+*/
+
+use crate::contract::loop_invariant;
+use crate::sorting::helper::{ge_seg, gt_seg, le_seg, lt_seg, should_check};
+#[cfg(any(debug_assertions, feature = "checked"))]
+use crate::sorting::helper::{is_partitioned_by, is_sorted};
+use std::ops::Range;
+
+/**
+Lower Bound
+
+Given a sorted array `arr`, returns the first index `i` such that `arr[i] >= x`,
+or `arr.len()` if there is none. `x` can be inserted at `i` keeping `arr` sorted.
+
+Time Complexity: `O(log n)`
+*/
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::requires(is_sorted(arr)),
+    contracts::ensures(ret <= arr.len()),
+    contracts::ensures(gt_seg(x, &arr[..ret])),
+    contracts::ensures(le_seg(x, &arr[ret..]))
+)]
+pub fn lower_bound<T: Ord>(arr: &[T], x: &T) -> usize {
+    let mut lo = 0;
+    let mut hi = arr.len();
+    let mut step = 0;
+    while lo < hi {
+        if should_check(step) {
+            loop_invariant!(gt_seg(x, &arr[..lo]));
+            loop_invariant!(le_seg(x, &arr[hi..]));
+        }
+        step += 1;
+
+        let mid = lo + (hi - lo) / 2;
+        if arr[mid] < *x {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    loop_invariant!(lo == hi);
+    lo
+}
+
+/**
+Upper Bound
+
+Given a sorted array `arr`, returns the first index `i` such that `arr[i] > x`,
+or `arr.len()` if there is none. `x` can be inserted at `i` keeping `arr` sorted.
+
+Time Complexity: `O(log n)`
+*/
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::requires(is_sorted(arr)),
+    contracts::ensures(ret <= arr.len()),
+    contracts::ensures(ge_seg(x, &arr[..ret])),
+    contracts::ensures(lt_seg(x, &arr[ret..]))
+)]
+pub fn upper_bound<T: Ord>(arr: &[T], x: &T) -> usize {
+    let mut lo = 0;
+    let mut hi = arr.len();
+    let mut step = 0;
+    while lo < hi {
+        if should_check(step) {
+            loop_invariant!(ge_seg(x, &arr[..lo]));
+            loop_invariant!(lt_seg(x, &arr[hi..]));
+        }
+        step += 1;
+
+        let mid = lo + (hi - lo) / 2;
+        if arr[mid] <= *x {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    loop_invariant!(lo == hi);
+    lo
+}
+
+/**
+Equal Range
+
+Given a sorted array `arr`, returns the range of indices whose elements are equal to `x`.
+The range is empty, and starts at the insertion point of `x`, if there are none.
+
+Time Complexity: `O(log n)`
+*/
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::requires(is_sorted(arr)),
+    contracts::ensures(ret.start <= ret.end && ret.end <= arr.len()),
+    contracts::ensures(arr[ret.clone()].iter().all(|y| y == x)),
+    contracts::ensures(gt_seg(x, &arr[..ret.start])),
+    contracts::ensures(lt_seg(x, &arr[ret.end..]))
+)]
+pub fn equal_range<T: Ord>(arr: &[T], x: &T) -> Range<usize> {
+    let start = lower_bound(arr, x);
+    // Everything before `start` is smaller, so only search the rest
+    let end = start + upper_bound(&arr[start..], x);
+    start..end
+}
+
+/**
+Binary Search
+
+Given a sorted array `arr`, returns `Ok(i)` with `arr[i] == x` if `x` occurs in `arr`
+(the first occurrence), and otherwise `Err(i)` where `i` is the insertion point of `x`.
+This has the same meaning as [slice::binary_search], except that the index of the first
+occurrence is guaranteed.
+
+Time Complexity: `O(log n)`
+*/
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::requires(is_sorted(arr)),
+    contracts::ensures(match ret { Ok(i) => arr[i] == *x, Err(i) => i <= arr.len() }),
+    contracts::ensures(gt_seg(x, &arr[..ret.unwrap_or_else(|i| i)]))
+)]
+pub fn binary_search<T: Ord>(arr: &[T], x: &T) -> Result<usize, usize> {
+    let i = lower_bound(arr, x);
+    if i < arr.len() && arr[i] == *x {
+        Ok(i)
+    } else {
+        Err(i)
+    }
+}
+
+/**
+Partition Point
+
+Given an array `arr` partitioned by `pred` (all elements satisfying `pred` come first),
+returns the number of elements satisfying `pred`, i.e. the index of the first one that does not.
+
+Time Complexity: `O(log n)` calls to `pred`
+*/
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::requires(is_partitioned_by(arr, &pred)),
+    contracts::ensures(ret <= arr.len()),
+    contracts::ensures(arr[..ret].iter().all(&pred)),
+    contracts::ensures(!arr[ret..].iter().any(&pred))
+)]
+pub fn partition_point<T, P: Fn(&T) -> bool>(arr: &[T], pred: P) -> usize {
+    let mut lo = 0;
+    let mut hi = arr.len();
+    let mut step = 0;
+    while lo < hi {
+        if should_check(step) {
+            loop_invariant!(arr[..lo].iter().all(&pred));
+            loop_invariant!(!arr[hi..].iter().any(&pred));
+        }
+        step += 1;
+
+        let mid = lo + (hi - lo) / 2;
+        if pred(&arr[mid]) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds_agree_with_std() {
+        let arr = [1, 2, 2, 2, 5, 7, 7, 9];
+        for x in 0..=10 {
+            assert_eq!(lower_bound(&arr, &x), arr.partition_point(|&y| y < x));
+            assert_eq!(upper_bound(&arr, &x), arr.partition_point(|&y| y <= x));
+            let range = equal_range(&arr, &x);
+            assert_eq!(range.len(), arr.iter().filter(|&&y| y == x).count());
+            match binary_search(&arr, &x) {
+                Ok(i) => assert_eq!(i, range.start),
+                Err(i) => {
+                    assert!(range.is_empty());
+                    assert_eq!(Err(i), arr.binary_search(&x));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn empty_and_single() {
+        let empty: [i32; 0] = [];
+        assert_eq!(lower_bound(&empty, &3), 0);
+        assert_eq!(upper_bound(&empty, &3), 0);
+        assert_eq!(binary_search(&empty, &3), Err(0));
+        assert_eq!(equal_range(&[4], &4), 0..1);
+        assert_eq!(binary_search(&[4], &5), Err(1));
+    }
+
+    #[test]
+    fn extreme_values_do_not_overflow() {
+        let arr = [u64::MIN, 1, u64::MAX - 1, u64::MAX, u64::MAX];
+        assert_eq!(equal_range(&arr, &u64::MAX), 3..5);
+        assert_eq!(binary_search(&arr, &u64::MIN), Ok(0));
+        assert_eq!(binary_search(&arr, &2), Err(2));
+    }
+
+    #[test]
+    fn partition_point_matches_std() {
+        let arr: Vec<i32> = (0..100).map(|i| i * 3).collect();
+        for t in -5..310 {
+            assert_eq!(
+                partition_point(&arr, |&y| y < t),
+                arr.partition_point(|&y| y < t)
+            );
+        }
+    }
+}
@@ -0,0 +1,265 @@
+/*!
+Searching Algorithms
+
+Binary Search on the Answer
+
+Implements:
+- [first_true]
+- [last_true]
+- [first_true_f64]
+
+Searches a monotone predicate over a range of numbers rather than over an array:
+"the smallest `x` such that `pred(x)`". Ranges are inclusive, so the full range of any
+integer type can be searched, and midpoints never overflow.
+
+Monotonicity is assumed. In debug mode it is checked on the visited points: the ends of
+the range, the bracket kept by the search, and the neighbour of the answer.
+*/
+
+use crate::contract::loop_invariant;
+use crate::sorting::helper::should_check;
+use std::fmt::Debug;
+
+/// Integer types that can be searched by [first_true] and [last_true]
+pub trait Integer: Copy + Ord + Debug {
+    /// `floor((lo + hi) / 2)` without overflow; `lo <= hi` is required
+    fn mid(lo: Self, hi: Self) -> Self;
+    /// `self + 1`; `self` must not be the maximum value
+    fn next(self) -> Self;
+    /// `self - 1`; `self` must not be the minimum value
+    fn prev(self) -> Self;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {$(
+        impl Integer for $t {
+            fn mid(lo: Self, hi: Self) -> Self {
+                // The distance is unsigned, and half of it always fits back into the type
+                lo + (hi.abs_diff(lo) / 2) as $t
+            }
+
+            fn next(self) -> Self {
+                self + 1
+            }
+
+            fn prev(self) -> Self {
+                self - 1
+            }
+        }
+    )*};
+}
+
+impl_integer!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
+
+/**
+First True
+
+Given an inclusive range `lo..=hi` and a predicate `pred` that is monotone on it
+(`false` up to some point, `true` from there on), returns the smallest `x` in the range
+such that `pred(x)`, or `None` if there is none.
+
+Time Complexity: `O(log (hi - lo))` calls to `pred`
+*/
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::requires(lo <= hi),
+    contracts::requires(!pred(lo) || pred(hi)),
+    contracts::ensures(ret.is_none_or(|x| lo <= x && x <= hi && pred(x))),
+    contracts::ensures(ret.is_none_or(|x| x == lo || !pred(x.prev()))),
+    contracts::ensures(ret.is_some() || !pred(hi))
+)]
+pub fn first_true<T: Integer, P: Fn(T) -> bool>(lo: T, hi: T, pred: P) -> Option<T> {
+    if !pred(hi) {
+        return None;
+    }
+    // The answer is in l..=r: pred(r) holds and, if l > lo, pred(l - 1) does not
+    let mut l = lo;
+    let mut r = hi;
+    let mut step = 0;
+    while l < r {
+        if should_check(step) {
+            loop_invariant!(pred(r));
+            loop_invariant!(l == lo || !pred(l.prev()));
+        }
+        step += 1;
+
+        let mid = T::mid(l, r);
+        loop_invariant!(l <= mid && mid < r);
+        if pred(mid) {
+            r = mid;
+        } else {
+            l = mid.next();
+        }
+    }
+    Some(l)
+}
+
+/**
+Last True
+
+Given an inclusive range `lo..=hi` and a predicate `pred` that is monotone on it
+(`true` up to some point, `false` from there on), returns the largest `x` in the range
+such that `pred(x)`, or `None` if there is none.
+
+Time Complexity: `O(log (hi - lo))` calls to `pred`
+*/
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::requires(lo <= hi),
+    contracts::requires(pred(lo) || !pred(hi)),
+    contracts::ensures(ret.is_none_or(|x| lo <= x && x <= hi && pred(x))),
+    contracts::ensures(ret.is_none_or(|x| x == hi || !pred(x.next()))),
+    contracts::ensures(ret.is_some() || !pred(lo))
+)]
+pub fn last_true<T: Integer, P: Fn(T) -> bool>(lo: T, hi: T, pred: P) -> Option<T> {
+    if !pred(lo) {
+        return None;
+    }
+    // pred(lo) holds, so the first failure (if any) is above lo
+    match first_true(lo, hi, |x| !pred(x)) {
+        Some(x) => Some(x.prev()),
+        None => Some(hi),
+    }
+}
+
+/**
+First True (real-valued bisection)
+
+Given an interval `[lo, hi]` and a predicate `pred` that is monotone on it
+(`false` below some threshold, `true` above it), returns an `x` such that `pred(x)`
+and the threshold is at most `tol` below `x`, or `None` if `pred(hi)` does not hold.
+
+Bisection stops early after `max_iterations` steps, or when no [f64] is left strictly
+inside the bracket; the result satisfies `pred` either way.
+
+Time Complexity: `O(min(log ((hi - lo) / tol), max_iterations))` calls to `pred`
+*/
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::requires(lo <= hi && lo.is_finite() && hi.is_finite()),
+    contracts::requires(tol >= 0.0),
+    contracts::requires(!pred(lo) || pred(hi)),
+    contracts::ensures(ret.is_none_or(|x| lo <= x && x <= hi && pred(x))),
+    contracts::ensures(ret.is_some() || !pred(hi))
+)]
+pub fn first_true_f64<P: Fn(f64) -> bool>(
+    lo: f64,
+    hi: f64,
+    tol: f64,
+    max_iterations: usize,
+    pred: P,
+) -> Option<f64> {
+    if !pred(hi) {
+        return None;
+    }
+    if pred(lo) {
+        return Some(lo);
+    }
+    // The threshold is in (l, r]: pred(l) does not hold, pred(r) does
+    let mut l = lo;
+    let mut r = hi;
+    for step in 0..max_iterations {
+        if should_check(step) {
+            loop_invariant!(!pred(l) && pred(r));
+        }
+        if r - l <= tol {
+            break;
+        }
+        // Halving first cannot overflow, unlike `l + r`
+        let mid = l / 2.0 + r / 2.0;
+        if mid <= l || mid >= r {
+            break;
+        }
+        if pred(mid) {
+            r = mid;
+        } else {
+            l = mid;
+        }
+    }
+    Some(r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{ENABLED, Kind, catch};
+
+    #[test]
+    fn boundaries_of_small_ranges() {
+        for threshold in 0..=11u32 {
+            assert_eq!(
+                first_true(0, 10, |x| x >= threshold),
+                (threshold <= 10).then_some(threshold)
+            );
+            assert_eq!(
+                last_true(0, 10, |x| x < threshold),
+                threshold.checked_sub(1).map(|t| t.min(10))
+            );
+        }
+        assert_eq!(first_true(5, 5, |_| true), Some(5));
+        assert_eq!(first_true(5, 5, |_| false), None);
+    }
+
+    #[test]
+    fn full_width_ranges() {
+        assert_eq!(
+            first_true(u64::MIN, u64::MAX, |x| x >= u64::MAX - 1),
+            Some(u64::MAX - 1)
+        );
+        assert_eq!(first_true(i64::MIN, i64::MAX, |x| x >= -3), Some(-3));
+        assert_eq!(
+            last_true(i64::MIN, i64::MAX, |x| x <= i64::MIN + 1),
+            Some(i64::MIN + 1)
+        );
+        assert_eq!(
+            first_true(0u128, u128::MAX, |x| x > 1 << 100),
+            Some((1 << 100) + 1)
+        );
+        assert_eq!(last_true(u128::MIN, u128::MAX, |_| true), Some(u128::MAX));
+    }
+
+    #[test]
+    fn capacity_planning() {
+        // Fewest servers, each handling 37 requests, to serve 1000 requests
+        assert_eq!(first_true(0u64, 1 << 40, |n| n * 37 >= 1000), Some(28));
+        // Largest batch with a total cost of at most 10^12
+        let cost = |b: u64| b * b + 3 * b;
+        assert_eq!(
+            last_true(0, 1 << 30, |b| cost(b) <= 1_000_000_000_000),
+            Some(999_998)
+        );
+    }
+
+    #[test]
+    fn real_bisection() {
+        let x = first_true_f64(0.0, 2.0, 1e-12, 100, |x| x * x >= 2.0).unwrap();
+        assert!(x * x >= 2.0);
+        assert!((x - std::f64::consts::SQRT_2).abs() <= 1e-12);
+
+        // Too few iterations still gives a point satisfying the predicate
+        let x = first_true_f64(0.0, 2.0, 0.0, 3, |x| x * x >= 2.0).unwrap();
+        assert_eq!(x, 1.5);
+
+        // Zero tolerance stops once the bracket holds adjacent floats
+        let x = first_true_f64(-f64::MAX, f64::MAX, 0.0, 10_000, |x| x >= 1.0).unwrap();
+        assert_eq!(x, 1.0);
+
+        assert_eq!(first_true_f64(0.0, 1.0, 1e-9, 100, |x| x > 5.0), None);
+        assert_eq!(first_true_f64(0.0, 1.0, 1e-9, 100, |_| true), Some(0.0));
+    }
+
+    #[test]
+    fn non_monotone_predicate_is_caught() {
+        // Both predicates break monotonicity at the ends of the range
+        let result = catch(|| first_true(0, 100, |x: i32| x % 7 == 0));
+        if ENABLED {
+            assert_eq!(result.unwrap_err().kind, Kind::Precondition);
+        }
+        let result = catch(|| last_true(0, 100, |x: i32| x > 50));
+        if ENABLED {
+            assert_eq!(result.unwrap_err().kind, Kind::Precondition);
+        }
+    }
+}
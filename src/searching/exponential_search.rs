@@ -0,0 +1,164 @@
+/*!
+Searching Algorithms
+
+Exponential (Galloping) Search
+
+Implements:
+- [lower_bound]
+- [lower_bound_from_end]
+- [unbounded_lower_bound]
+
+Galloping probes indices `1, 2, 4, 8, ...` away from one end until it passes the target,
+then binary searches the last gap. Finding an answer at distance `d` from that end costs
+`O(log d)` comparisons instead of `O(log n)`.
+*/
+
+use crate::contract::loop_invariant;
+use crate::searching::binary_search;
+#[cfg(any(debug_assertions, feature = "checked"))]
+use crate::sorting::helper::is_sorted;
+use crate::sorting::helper::{gt_seg, le_seg, should_check};
+
+/**
+Exponential Search from the front
+
+Given a sorted array `arr`, returns the first index `i` such that `arr[i] >= x`,
+or `arr.len()` if there is none. Same result as [binary_search::lower_bound].
+
+Time Complexity: `O(log i)`
+*/
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::requires(is_sorted(arr)),
+    contracts::ensures(ret <= arr.len()),
+    contracts::ensures(gt_seg(x, &arr[..ret])),
+    contracts::ensures(le_seg(x, &arr[ret..]))
+)]
+pub fn lower_bound<T: Ord>(arr: &[T], x: &T) -> usize {
+    let n = arr.len();
+    // Gallop: everything before `lo` is less than `x`
+    let mut lo = 0;
+    let mut step: usize = 1;
+    while lo < n && arr[lo] < *x {
+        if should_check(step.trailing_zeros() as usize) {
+            loop_invariant!(gt_seg(x, &arr[..lo]));
+        }
+        let next = lo.saturating_add(step).min(n);
+        if next < n && arr[next] < *x {
+            lo = next + 1;
+            step = step.saturating_mul(2);
+        } else {
+            // arr[next] >= x (or next == n): the answer is in (lo, next]
+            loop_invariant!(arr[lo] < *x);
+            return lo + 1 + binary_search::lower_bound(&arr[lo + 1..next], x);
+        }
+    }
+    lo
+}
+
+/**
+Exponential Search from the back
+
+Given a sorted array `arr`, returns the first index `i` such that `arr[i] >= x`,
+or `arr.len()` if there is none. Same result as [binary_search::lower_bound],
+but fast when the answer is close to the end, e.g. recent entries of a time series.
+
+Time Complexity: `O(log (n - i))`
+*/
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::requires(is_sorted(arr)),
+    contracts::ensures(ret <= arr.len()),
+    contracts::ensures(gt_seg(x, &arr[..ret])),
+    contracts::ensures(le_seg(x, &arr[ret..]))
+)]
+pub fn lower_bound_from_end<T: Ord>(arr: &[T], x: &T) -> usize {
+    // Gallop backwards: everything from `hi` on is at least `x`
+    let mut hi = arr.len();
+    let mut step: usize = 1;
+    while hi > 0 && arr[hi - 1] >= *x {
+        if should_check(step.trailing_zeros() as usize) {
+            loop_invariant!(le_seg(x, &arr[hi..]));
+        }
+        let next = hi.saturating_sub(1 + step);
+        if arr[next] >= *x {
+            if next == 0 {
+                return 0;
+            }
+            hi = next;
+            step = step.saturating_mul(2);
+        } else {
+            // arr[next] < x <= arr[hi - 1]: the answer is in (next, hi - 1]
+            return next + 1 + binary_search::lower_bound(&arr[next + 1..hi - 1], x);
+        }
+    }
+    hi
+}
+
+/**
+Unbounded Exponential Search
+
+Given a nondecreasing sequence `f(0), f(1), f(2), ...` of unknown length, returns the
+first index `i` such that `f(i) >= x`, or `None` if there is none up to `usize::MAX`.
+
+Monotonicity is assumed, and checked in debug mode on every pair of consecutive probes.
+
+Time Complexity: `O(log i)` calls to `f`
+*/
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::ensures(ret.is_none_or(|i| f(i) >= *x && (i == 0 || f(i - 1) < *x)))
+)]
+pub fn unbounded_lower_bound<T: Ord, F: Fn(usize) -> T>(f: F, x: &T) -> Option<usize> {
+    if f(0) >= *x {
+        return Some(0);
+    }
+    // Invariant: f(lo) < x
+    let mut lo = 0usize;
+    let mut step = 1usize;
+    loop {
+        let next = lo.checked_add(step)?;
+        let value = f(next);
+        loop_invariant!(f(lo) <= value);
+        if value >= *x {
+            // The answer is in (lo, next]
+            let mut lo = lo;
+            let mut hi = next;
+            while hi - lo > 1 {
+                let mid = lo + (hi - lo) / 2;
+                if f(mid) < *x {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            return Some(hi);
+        }
+        lo = next;
+        step = step.checked_mul(2)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn agrees_with_binary_search() {
+        for n in 0..40 {
+            let arr: Vec<i32> = (0..n).map(|i| i / 3 * 2).collect();
+            for x in -2..=30 {
+                let expected = binary_search::lower_bound(&arr, &x);
+                assert_eq!(lower_bound(&arr, &x), expected, "n={n}, x={x}");
+                assert_eq!(lower_bound_from_end(&arr, &x), expected, "n={n}, x={x}");
+            }
+        }
+    }
+
+    #[test]
+    fn unbounded_sequence() {
+        assert_eq!(unbounded_lower_bound(|i| i * i, &1_000_001), Some(1001));
+        assert_eq!(unbounded_lower_bound(|i| i, &0), Some(0));
+        assert_eq!(unbounded_lower_bound(|i| i as u128, &(1u128 << 70)), None);
+    }
+}
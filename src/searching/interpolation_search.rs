@@ -0,0 +1,150 @@
+/*!
+Searching Algorithms
+
+Interpolation Search
+
+Implements:
+- [lower_bound]
+- [search]
+
+Instead of probing the middle, probes where `x` would be if the keys were evenly spaced
+between the two ends of the remaining range. Any integer type that converts losslessly
+into [i128] can be used as a key.
+*/
+
+use crate::contract::loop_invariant;
+#[cfg(any(debug_assertions, feature = "checked"))]
+use crate::sorting::helper::is_sorted;
+use crate::sorting::helper::{gt_seg, le_seg, should_check};
+
+/// Index in `lo..hi` where `x` is expected, assuming evenly spaced keys
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::requires(hi - lo >= 2),
+    contracts::requires(a < x && x <= b),
+    contracts::ensures(lo <= ret && ret < hi)
+)]
+fn probe(lo: usize, hi: usize, a: i128, b: i128, x: i128) -> usize {
+    // Cannot overflow for keys of up to 64 bits; wider keys fall back to the midpoint
+    match x.abs_diff(a).checked_mul((hi - 1 - lo) as u128) {
+        Some(product) => lo + (product / b.abs_diff(a)) as usize,
+        None => lo + (hi - lo) / 2,
+    }
+}
+
+/**
+Interpolation Search
+
+Given a sorted array `arr` of integer keys, returns the first index `i` such that
+`arr[i] >= x`, or `arr.len()` if there is none.
+
+Every interpolation probe that fails to halve the remaining range is followed by a
+bisection step, so the worst case stays logarithmic on skewed keys.
+
+Average-case Time Complexity: `O(log log n)` for uniformly distributed keys
+Worst-case Time Complexity: `O(log n)`
+*/
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::requires(is_sorted(arr)),
+    contracts::ensures(ret <= arr.len()),
+    contracts::ensures(gt_seg(&x, &arr[..ret])),
+    contracts::ensures(le_seg(&x, &arr[ret..]))
+)]
+pub fn lower_bound<T: Ord + Copy + Into<i128>>(arr: &[T], x: T) -> usize {
+    let key = x.into();
+    let mut lo = 0;
+    let mut hi = arr.len();
+    let mut step = 0;
+    let mut bisect = false;
+    while lo < hi {
+        if should_check(step) {
+            loop_invariant!(gt_seg(&x, &arr[..lo]));
+            loop_invariant!(le_seg(&x, &arr[hi..]));
+        }
+        step += 1;
+
+        let a = arr[lo].into();
+        let b = arr[hi - 1].into();
+        if key <= a {
+            return lo;
+        }
+        if key > b {
+            return hi;
+        }
+        // arr[lo] < x <= arr[hi - 1], so at least two elements remain
+        let len = hi - lo;
+        let mid = if bisect {
+            lo + len / 2
+        } else {
+            probe(lo, hi, a, b, key)
+        };
+        if arr[mid] < x {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+        bisect = !bisect && hi - lo > len / 2;
+    }
+    lo
+}
+
+/**
+Interpolation Search
+
+Given a sorted array `arr` of integer keys, returns `Ok(i)` with `arr[i] == x` for the first
+occurrence of `x`, and otherwise `Err(i)` where `i` is the insertion point of `x`.
+
+Average-case Time Complexity: `O(log log n)` for uniformly distributed keys
+Worst-case Time Complexity: `O(log n)`
+*/
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::requires(is_sorted(arr)),
+    contracts::ensures(match ret { Ok(i) => arr[i] == x, Err(i) => i <= arr.len() }),
+    contracts::ensures(gt_seg(&x, &arr[..ret.unwrap_or_else(|i| i)]))
+)]
+pub fn search<T: Ord + Copy + Into<i128>>(arr: &[T], x: T) -> Result<usize, usize> {
+    let i = lower_bound(arr, x);
+    if i < arr.len() && arr[i] == x {
+        Ok(i)
+    } else {
+        Err(i)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::searching::binary_search;
+
+    #[test]
+    fn agrees_with_binary_search() {
+        let uniform: Vec<i64> = (0..500).map(|i| i * 7 - 1000).collect();
+        let skewed: Vec<i64> = (0..60).map(|i| 1i64 << i).collect();
+        let duplicates: Vec<i64> = (0..100).map(|i| i / 10).collect();
+        for arr in [uniform, skewed, duplicates] {
+            for &x in arr.iter().chain(&[-2000, -1, 0, 3, 5000, i64::MAX - 1]) {
+                for x in [x - 1, x, x + 1] {
+                    assert_eq!(lower_bound(&arr, x), binary_search::lower_bound(&arr, &x));
+                    assert_eq!(search(&arr, x), binary_search::binary_search(&arr, &x));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn extreme_keys_do_not_overflow() {
+        let arr = [u64::MIN, 1, u64::MAX / 2, u64::MAX - 1, u64::MAX];
+        assert_eq!(search(&arr, u64::MAX), Ok(4));
+        assert_eq!(search(&arr, u64::MAX - 2), Err(3));
+        let arr = [i64::MIN, -1, 0, i64::MAX];
+        assert_eq!(search(&arr, i64::MIN), Ok(0));
+        assert_eq!(search(&arr, 5), Err(3));
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(search::<u32>(&[], 3), Err(0));
+    }
+}
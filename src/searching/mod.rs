@@ -0,0 +1,5 @@
+pub mod binary_search;
+pub mod bisect;
+pub mod exponential_search;
+pub mod interpolation_search;
+pub mod ternary_search;
@@ -0,0 +1,197 @@
+/*!
+Searching Algorithms
+
+Ternary and Golden-Section Search
+
+Implements:
+- [argmax]
+- [argmin]
+- [golden_section_max]
+- [golden_section_min]
+
+Finds the extremum of a unimodal function: over integers by ternary search,
+over [f64] by golden-section search, which reuses one evaluation per step.
+*/
+
+use crate::contract::loop_invariant;
+use crate::sorting::helper::should_check;
+use std::cmp::Reverse;
+
+/// `1 / phi`, the factor by which golden-section search shrinks the interval per step
+const INV_PHI: f64 = 0.618_033_988_749_894_9;
+
+/// Golden-section search stops after this many steps even if the tolerance is not reached,
+/// e.g. because it is below the spacing of [f64] values around the extremum.
+pub const MAX_ITERATIONS: usize = 4096;
+
+/**
+Ternary Search (maximum)
+
+Given a range `lo..=hi` and a function `f` that is strictly increasing and then strictly
+decreasing on it (either part may be empty), returns the `x` in the range maximizing `f(x)`.
+
+Unimodality is assumed, and checked in debug mode on the points visited.
+
+Time Complexity: `O(log (hi - lo))` calls to `f`
+*/
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::requires(lo <= hi),
+    contracts::ensures(lo <= ret && ret <= hi),
+    contracts::ensures(ret == lo || f(ret - 1) < f(ret)),
+    contracts::ensures(ret == hi || f(ret + 1) < f(ret))
+)]
+pub fn argmax<T: Ord, F: Fn(i64) -> T>(lo: i64, hi: i64, f: F) -> i64 {
+    // The maximum is in l..=r
+    let mut l = lo;
+    let mut r = hi;
+    let mut step = 0;
+    // abs_diff cannot overflow, even for the full range of i64
+    while r.abs_diff(l) > 2 {
+        if should_check(step) {
+            loop_invariant!(l == lo || f(l - 1) < f(l));
+            loop_invariant!(r == hi || f(r + 1) < f(r));
+        }
+        step += 1;
+
+        let third = (r.abs_diff(l) / 3) as i64;
+        let m1 = l + third;
+        let m2 = r - third;
+        loop_invariant!(l < m1 && m1 < m2 && m2 < r);
+        if f(m1) < f(m2) {
+            // m1 is on the increasing side
+            l = m1 + 1;
+        } else {
+            // m2 is on the decreasing side, or the maximum is strictly between them
+            r = m2 - 1;
+        }
+    }
+    loop_invariant!(l <= r);
+
+    let mut best = l;
+    for x in l + 1..=r {
+        if f(x) > f(best) {
+            best = x;
+        }
+    }
+    best
+}
+
+/**
+Ternary Search (minimum)
+
+Given a range `lo..=hi` and a function `f` that is strictly decreasing and then strictly
+increasing on it (either part may be empty), returns the `x` in the range minimizing `f(x)`.
+
+Time Complexity: `O(log (hi - lo))` calls to `f`
+*/
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::requires(lo <= hi),
+    contracts::ensures(lo <= ret && ret <= hi),
+    contracts::ensures(ret == lo || f(ret - 1) > f(ret)),
+    contracts::ensures(ret == hi || f(ret + 1) > f(ret))
+)]
+pub fn argmin<T: Ord, F: Fn(i64) -> T>(lo: i64, hi: i64, f: F) -> i64 {
+    argmax(lo, hi, |x| Reverse(f(x)))
+}
+
+/**
+Golden-Section Search (maximum)
+
+Given an interval `[lo, hi]` and a function `f` that is unimodal on it, returns an `x` in the
+interval within about `tol` of the maximizer of `f`. Stops after [MAX_ITERATIONS] steps.
+
+Time Complexity: `O(log ((hi - lo) / tol))` calls to `f`
+*/
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::requires(lo <= hi && (hi - lo).is_finite()),
+    contracts::requires(tol > 0.0),
+    contracts::ensures(lo <= ret && ret <= hi)
+)]
+pub fn golden_section_max<F: Fn(f64) -> f64>(lo: f64, hi: f64, tol: f64, f: F) -> f64 {
+    // The maximum is in [a, b]; c < d are the interior probes
+    let mut a = lo;
+    let mut b = hi;
+    let mut c = (b - (b - a) * INV_PHI).clamp(a, b);
+    let mut d = (a + (b - a) * INV_PHI).clamp(a, b);
+    let mut fc = f(c);
+    let mut fd = f(d);
+    let mut step = 0;
+    // Once the probes meet, f64 cannot resolve the interval any further
+    while b - a > tol && c < d && step < MAX_ITERATIONS {
+        loop_invariant!(lo <= a && a <= c && c <= b && b <= hi);
+        step += 1;
+
+        if fc > fd {
+            // The maximum is not in (d, b]
+            b = d;
+            d = c;
+            fd = fc;
+            c = (b - (b - a) * INV_PHI).clamp(a, b);
+            fc = f(c);
+        } else {
+            // The maximum is not in [a, c)
+            a = c;
+            c = d;
+            fc = fd;
+            d = (a + (b - a) * INV_PHI).clamp(a, b);
+            fd = f(d);
+        }
+    }
+    (a + (b - a) / 2.0).clamp(lo, hi)
+}
+
+/**
+Golden-Section Search (minimum)
+
+Given an interval `[lo, hi]` and a function `f` that is unimodal on it, returns an `x` in the
+interval within about `tol` of the minimizer of `f`. Stops after [MAX_ITERATIONS] steps.
+
+Time Complexity: `O(log ((hi - lo) / tol))` calls to `f`
+*/
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::requires(lo <= hi && (hi - lo).is_finite()),
+    contracts::requires(tol > 0.0),
+    contracts::ensures(lo <= ret && ret <= hi)
+)]
+pub fn golden_section_min<F: Fn(f64) -> f64>(lo: f64, hi: f64, tol: f64, f: F) -> f64 {
+    golden_section_max(lo, hi, tol, |x| -f(x))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integer_extrema() {
+        for peak in -20..=20 {
+            let f = |x: i64| -(x - peak).abs();
+            assert_eq!(argmax(-20, 20, f), peak);
+            assert_eq!(argmin(-20, 20, |x| (x - peak) * (x - peak)), peak);
+        }
+        // Monotone functions peak at an end
+        assert_eq!(argmax(0, 100, |x| x), 100);
+        assert_eq!(argmax(0, 100, |x| -x), 0);
+        assert_eq!(argmax(7, 7, |x| x), 7);
+    }
+
+    #[test]
+    fn full_range_does_not_overflow() {
+        assert_eq!(argmax(i64::MIN, i64::MAX, |x| Reverse(x.unsigned_abs())), 0);
+        assert_eq!(argmin(i64::MIN, i64::MAX, |x| x), i64::MIN);
+    }
+
+    #[test]
+    fn golden_section() {
+        let x = golden_section_max(-10.0, 10.0, 1e-9, |x| -(x - 1.5) * (x - 1.5));
+        assert!((x - 1.5).abs() < 1e-6);
+        let x = golden_section_min(0.0, 4.0, 1e-9, |x: f64| x.cos());
+        assert!((x - std::f64::consts::PI).abs() < 1e-6);
+        // A tolerance below f64 spacing still terminates
+        let x = golden_section_min(1e6, 1e6 + 1.0, f64::MIN_POSITIVE, |x| x);
+        assert!((1e6..1e6 + 1e-3).contains(&x));
+    }
+}
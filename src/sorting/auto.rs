@@ -0,0 +1,371 @@
+/*!
+Sorting Algorithms
+
+Adaptive Algorithm Chooser
+
+Inspects the input and dispatches to the best-suited algorithm of this module.
+
+Implements:
+- [sort]
+- [explain]
+- [profile]
+*/
+
+#[cfg(any(debug_assertions, feature = "checked"))]
+use crate::sorting::helper::{is_permutation_of, is_sorted};
+use crate::sorting::natural_merge_sort::run_end;
+use crate::sorting::{
+    heap_sort, insertion_sort, natural_merge_sort, quick_sort, three_way_quick_sort,
+};
+use std::fmt;
+use std::mem::size_of;
+
+/// Inputs of at most this many elements are sorted with insertion sort.
+pub const TINY_LEN: usize = 16;
+
+/// Elements larger than this many bytes are considered expensive to clone.
+pub const LARGE_ELEMENT_SIZE: usize = 64;
+
+/// Number of elements (and of element pairs) inspected by the sampling estimators.
+pub const SAMPLE_SIZE: usize = 64;
+
+/// An input is nearly sorted if at most this fraction of sampled pairs is inverted...
+pub const NEARLY_SORTED_INVERSIONS: f64 = 0.05;
+
+/// ...or if it consists of at most `len / NEARLY_SORTED_RUN_DIVISOR` runs.
+pub const NEARLY_SORTED_RUN_DIVISOR: usize = 32;
+
+/// An input has low cardinality if at most this fraction of sampled elements is distinct.
+pub const LOW_CARDINALITY_DISTINCT: f64 = 0.25;
+
+/// The algorithms [sort] may dispatch to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    /// [insertion_sort::sort]
+    Insertion,
+    /// [natural_merge_sort::sort]
+    NaturalMerge,
+    /// [three_way_quick_sort::sort]
+    ThreeWayQuick,
+    /// [heap_sort::sort]
+    Heap,
+    /// [quick_sort::sort]
+    Quick,
+}
+
+impl Algorithm {
+    /// Human readable name of the algorithm
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::Insertion => "insertion sort",
+            Algorithm::NaturalMerge => "natural merge sort",
+            Algorithm::ThreeWayQuick => "three-way quick sort",
+            Algorithm::Heap => "heap sort",
+            Algorithm::Quick => "quick sort",
+        }
+    }
+}
+
+/// Why [explain] picked an [Algorithm].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Reason {
+    /// At most [TINY_LEN] elements; the quadratic algorithm has the smallest constant.
+    Tiny,
+    /// Few runs or few sampled inversions; merging the existing runs is close to linear.
+    NearlySorted,
+    /// Few distinct values; grouping equal keys avoids quick sort's quadratic case.
+    LowCardinality,
+    /// Elements larger than [LARGE_ELEMENT_SIZE] bytes; sort in place without cloning.
+    LargeElements,
+    /// No particular structure detected.
+    General,
+}
+
+/// The measurements [explain] bases its decision on.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Profile {
+    /// Number of elements
+    pub len: usize,
+    /// Size of one element in bytes
+    pub element_size: usize,
+    /// Number of runs natural merge sort splits the input into: maximal nondecreasing or
+    /// strictly descending ones, the latter being reversed (`0` for an empty input)
+    pub runs: usize,
+    /// Fraction of sampled pairs `i < j` with `arr[i] > arr[j]`
+    pub inversion_ratio: f64,
+    /// Fraction of distinct values among the sampled elements
+    pub distinct_ratio: f64,
+}
+
+/// The outcome of [explain]: which algorithm, why, and the measurements behind it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Choice {
+    pub algorithm: Algorithm,
+    pub reason: Reason,
+    pub profile: Profile,
+}
+
+impl fmt::Display for Choice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let p = &self.profile;
+        write!(f, "{}: ", self.algorithm.name())?;
+        match self.reason {
+            Reason::Tiny => write!(f, "only {} elements (<= {})", p.len, TINY_LEN),
+            Reason::NearlySorted => write!(
+                f,
+                "nearly sorted ({} runs, {:.1}% of sampled pairs inverted)",
+                p.runs,
+                p.inversion_ratio * 100.0
+            ),
+            Reason::LowCardinality => write!(
+                f,
+                "many duplicates ({:.1}% of sampled elements distinct)",
+                p.distinct_ratio * 100.0
+            ),
+            Reason::LargeElements => write!(
+                f,
+                "large elements ({} bytes > {}), sorting in place without clones",
+                p.element_size, LARGE_ELEMENT_SIZE
+            ),
+            Reason::General => write!(f, "no exploitable structure detected"),
+        }
+    }
+}
+
+/// Deterministic xorshift64* generator, so that the same input always gets the same choice.
+fn next_random(state: &mut u64) -> u64 {
+    let mut z = *state;
+    z ^= z >> 12;
+    z ^= z << 25;
+    z ^= z >> 27;
+    *state = z;
+    z.wrapping_mul(2685821657736338717)
+}
+
+/// Counts the runs of `arr` the way [natural_merge_sort::sort] splits it, so that descending
+/// input is nearly sorted too
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::ensures(ret <= arr.len()),
+    contracts::ensures(arr.is_empty() || ret >= 1),
+    contracts::ensures(ret <= 1 || !is_sorted(arr))
+)]
+fn count_runs<T: Ord>(arr: &[T]) -> usize {
+    let mut runs = 0;
+    let mut start = 0;
+    while start < arr.len() {
+        start = run_end(arr, start).0;
+        runs += 1;
+    }
+    runs
+}
+
+/// Estimates the fraction of inverted pairs from [SAMPLE_SIZE] random pairs
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::ensures((0.0..=1.0).contains(&ret))
+)]
+fn sampled_inversion_ratio<T: Ord>(arr: &[T]) -> f64 {
+    let n = arr.len();
+    if n < 2 {
+        return 0.0;
+    }
+    let mut state = 0x9E37_79B9_7F4A_7C15u64 ^ n as u64;
+    let mut inverted = 0;
+    for _ in 0..SAMPLE_SIZE {
+        let a = (next_random(&mut state) % n as u64) as usize;
+        let b = (next_random(&mut state) % n as u64) as usize;
+        let (i, j) = if a <= b { (a, b) } else { (b, a) };
+        if arr[i] > arr[j] {
+            inverted += 1;
+        }
+    }
+    inverted as f64 / SAMPLE_SIZE as f64
+}
+
+/// Estimates the fraction of distinct values from [SAMPLE_SIZE] evenly spaced elements
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::ensures((0.0..=1.0).contains(&ret))
+)]
+fn sampled_distinct_ratio<T: Ord>(arr: &[T]) -> f64 {
+    let n = arr.len();
+    if n == 0 {
+        return 1.0;
+    }
+    let k = n.min(SAMPLE_SIZE);
+    // Index i * n / k never overflows in u128 and stays below n
+    let mut sample: Vec<&T> = (0..k)
+        .map(|i| &arr[(i as u128 * n as u128 / k as u128) as usize])
+        .collect();
+    sample.sort();
+    sample.dedup();
+    sample.len() as f64 / k as f64
+}
+
+/// Measures the properties of `arr` that [explain] bases its decision on.
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::ensures(ret.len == arr.len())
+)]
+pub fn profile<T: Ord>(arr: &[T]) -> Profile {
+    Profile {
+        len: arr.len(),
+        element_size: size_of::<T>(),
+        runs: count_runs(arr),
+        inversion_ratio: sampled_inversion_ratio(arr),
+        distinct_ratio: sampled_distinct_ratio(arr),
+    }
+}
+
+/// Decides which algorithm [sort] would use on `arr` and why, without modifying it.
+///
+/// The rules are checked in order:
+/// 1. at most [TINY_LEN] elements: insertion sort;
+/// 2. nearly sorted (few runs or few sampled inversions): natural merge sort;
+/// 3. low cardinality (few sampled distinct values): three-way quick sort;
+/// 4. elements larger than [LARGE_ELEMENT_SIZE] bytes: heap sort;
+/// 5. otherwise: quick sort.
+pub fn explain<T: Ord>(arr: &[T]) -> Choice {
+    let profile = profile(arr);
+    let (algorithm, reason) = if profile.len <= TINY_LEN {
+        (Algorithm::Insertion, Reason::Tiny)
+    } else if profile.runs <= (profile.len / NEARLY_SORTED_RUN_DIVISOR).max(2)
+        || profile.inversion_ratio <= NEARLY_SORTED_INVERSIONS
+    {
+        (Algorithm::NaturalMerge, Reason::NearlySorted)
+    } else if profile.distinct_ratio <= LOW_CARDINALITY_DISTINCT {
+        (Algorithm::ThreeWayQuick, Reason::LowCardinality)
+    } else if profile.element_size > LARGE_ELEMENT_SIZE {
+        (Algorithm::Heap, Reason::LargeElements)
+    } else {
+        (Algorithm::Quick, Reason::General)
+    };
+    Choice {
+        algorithm,
+        reason,
+        profile,
+    }
+}
+
+/// Sorts `arr` with the given algorithm
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::ensures(is_sorted(arr)),
+    contracts::ensures(is_permutation_of(arr, &old(arr.to_vec())))
+)]
+pub fn sort_with<T: Ord + Clone>(algorithm: Algorithm, arr: &mut [T]) {
+    match algorithm {
+        Algorithm::Insertion => insertion_sort::sort(arr),
+        Algorithm::NaturalMerge => natural_merge_sort::sort(arr),
+        Algorithm::ThreeWayQuick => three_way_quick_sort::sort(arr),
+        Algorithm::Heap => heap_sort::sort(arr),
+        Algorithm::Quick => quick_sort::sort(arr),
+    }
+}
+
+/**
+Adaptive Sort
+
+Given an array `arr` containing `n` elements of type `T`, sort it in-place in nondecreasing order,
+using the algorithm picked by [explain]. The returned [Choice] reports which algorithm
+was used and why.
+
+`T` must implement [Ord] and [Clone].
+
+Worst-case Time Complexity: that of the chosen algorithm, plus `O(n)` for the inspection
+Space Complexity: that of the chosen algorithm, plus `O(1)` for the inspection
+Stable?: No
+*/
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::ensures(is_sorted(arr)),
+    contracts::ensures(is_permutation_of(arr, &old(arr.to_vec())))
+)]
+pub fn sort<T: Ord + Clone>(arr: &mut [T]) -> Choice {
+    let choice = explain(arr);
+    sort_with(choice.algorithm, arr);
+    choice
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sorting::helper::is_sorted;
+    use crate::sorting::sort_test_helper::test_sort;
+
+    #[test]
+    fn test_auto_sort() {
+        test_sort(|arr| {
+            sort(arr);
+        });
+    }
+
+    #[test]
+    fn explain_tiny() {
+        let arr = [3, 1, 2];
+        let choice = explain(&arr);
+        assert_eq!(choice.algorithm, Algorithm::Insertion);
+        assert_eq!(choice.reason, Reason::Tiny);
+    }
+
+    #[test]
+    fn explain_nearly_sorted() {
+        let mut arr: Vec<i32> = (0..1000).collect();
+        arr.swap(10, 900);
+        let choice = explain(&arr);
+        assert_eq!(choice.algorithm, Algorithm::NaturalMerge);
+        assert_eq!(choice.reason, Reason::NearlySorted);
+        assert_eq!(choice.profile.runs, 3);
+    }
+
+    #[test]
+    fn explain_descending() {
+        let mut arr: Vec<i32> = (0..1000).rev().collect();
+        let choice = explain(&arr);
+        assert_eq!(choice.algorithm, Algorithm::NaturalMerge);
+        assert_eq!(choice.reason, Reason::NearlySorted);
+        assert_eq!(choice.profile.runs, 1);
+
+        // Mostly descending: each ascending step starts a new descending run
+        arr.swap(100, 101);
+        arr.swap(500, 501);
+        assert_eq!(count_runs(&arr), 3);
+        assert_eq!(sort(&mut arr).reason, Reason::NearlySorted);
+        assert!(is_sorted(&arr));
+
+        // Equal neighbours end a strictly descending run
+        assert_eq!(count_runs(&[3, 2, 2, 1]), 2);
+        assert_eq!(count_runs::<i32>(&[]), 0);
+    }
+
+    #[test]
+    fn explain_low_cardinality() {
+        let mut state = 42;
+        let arr: Vec<u64> = (0..1000).map(|_| next_random(&mut state) % 3).collect();
+        let choice = explain(&arr);
+        assert_eq!(choice.algorithm, Algorithm::ThreeWayQuick);
+        assert_eq!(choice.reason, Reason::LowCardinality);
+    }
+
+    #[test]
+    fn explain_general_and_large_elements() {
+        let mut state = 42;
+        let arr: Vec<u64> = (0..1000).map(|_| next_random(&mut state)).collect();
+        assert_eq!(explain(&arr).reason, Reason::General);
+
+        let wide: Vec<[u64; 16]> = arr.iter().map(|&x| [x; 16]).collect();
+        let mut sorted = wide.clone();
+        let choice = sort(&mut sorted);
+        assert_eq!(choice.reason, Reason::LargeElements);
+        assert_eq!(choice.algorithm, Algorithm::Heap);
+        assert!(is_sorted(&sorted));
+    }
+
+    #[test]
+    fn explanation_mentions_algorithm() {
+        let arr = [2, 1];
+        let text = explain(&arr).to_string();
+        assert!(text.starts_with("insertion sort"));
+    }
+}
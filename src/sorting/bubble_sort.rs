@@ -7,15 +7,18 @@ Implements:
 - [sort]
 */
 
-use crate::sorting::helper::is_sorted;
-use contracts::debug_ensures;
+use crate::contract::Snapshot;
+use crate::contract::loop_invariant;
+#[cfg(any(debug_assertions, feature = "checked"))]
+use crate::sorting::helper::is_permutation_of;
+use crate::sorting::helper::{is_sorted, should_check};
 
 /**
 Bubble Sort
 
 Given an array `arr` containing `n` elements of type `T`, sort it in-place in nondecreasing order.
 
-`T` must implement [Ord].
+`T` must implement [Ord], and [Clone] in builds that check contracts ([Snapshot]).
 
 Worst-case Time Complexity: `O(n^2)`
 Average-case Time Complexity: `O(n^2)`
@@ -23,14 +26,17 @@ Best-case Time Complexity: `O(n)`
 Space Complexity: `O(1)`
 Stable?: Yes
 */
-#[debug_ensures(is_sorted(arr))]
-pub fn sort<T: Ord>(arr: &mut [T]) {
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::ensures(is_sorted(arr)),
+    contracts::ensures(is_permutation_of(arr, &old(arr.to_vec())))
+)]
+pub fn sort<T: Ord + Snapshot>(arr: &mut [T]) {
     let n = arr.len();
-    let mut flag = true;
     for cnt in 1..=n {
-        flag = false;
+        let mut flag = false;
         for i in 1..n {
-            if !(arr[i - 1] <= arr[i]) {
+            if arr[i - 1] > arr[i] {
                 flag = true;
                 arr.swap(i - 1, i);
             }
@@ -41,7 +47,9 @@ pub fn sort<T: Ord>(arr: &mut [T]) {
 
         // Loop Invariants
         // The last cnt elements are sorted.
-        debug_assert!(is_sorted(&arr[n - cnt..]));
+        if should_check(cnt) {
+            loop_invariant!(is_sorted(&arr[n - cnt..]));
+        }
     }
 }
 
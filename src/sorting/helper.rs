@@ -3,8 +3,102 @@ Sorting Algorithms — Helper Functions
 
 These helpers operate on the entire slice you pass in. If you want to check a subarray,
 use Rust slicing syntax (e.g., `&arr[lo..hi]`) when calling them.
+
+They form the specification vocabulary of this crate and may be used in downstream contracts.
+
+Most predicates take linear time, so checking one in every iteration of a loop makes the
+loop quadratic. Loop invariants are therefore guarded by [should_check], which implements the
+[CheckStrategy] selected at runtime with [set_check_strategy].
 */
 
+use crate::contract;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// How often [should_check] lets a loop invariant be checked.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CheckStrategy {
+    /// Check at every step.
+    Always,
+    /// Check at steps `0, 1, 2, 4, 8, ...`: a linear-time invariant costs amortized `O(1)`
+    /// per step, and a failure is still caught by the next check at most twice as late.
+    PowersOfTwo,
+    /// Check each step independently with probability `1 / one_in`.
+    Sampled { one_in: u32 },
+    /// Never check.
+    Never,
+}
+
+// Tag in the upper 32 bits, `one_in` in the lower 32 bits
+const TAG_ALWAYS: u64 = 0;
+const TAG_POWERS_OF_TWO: u64 = 1;
+const TAG_SAMPLED: u64 = 2;
+const TAG_NEVER: u64 = 3;
+
+static CHECK_STRATEGY: AtomicU64 = AtomicU64::new(TAG_POWERS_OF_TWO << 32);
+
+thread_local! {
+    static SAMPLER: Cell<u64> = const { Cell::new(0x9E37_79B9_7F4A_7C15) };
+}
+
+impl CheckStrategy {
+    fn encode(self) -> u64 {
+        match self {
+            CheckStrategy::Always => TAG_ALWAYS << 32,
+            CheckStrategy::PowersOfTwo => TAG_POWERS_OF_TWO << 32,
+            CheckStrategy::Sampled { one_in } => (TAG_SAMPLED << 32) | one_in.max(1) as u64,
+            CheckStrategy::Never => TAG_NEVER << 32,
+        }
+    }
+
+    fn decode(encoded: u64) -> Self {
+        match encoded >> 32 {
+            TAG_ALWAYS => CheckStrategy::Always,
+            TAG_POWERS_OF_TWO => CheckStrategy::PowersOfTwo,
+            TAG_SAMPLED => CheckStrategy::Sampled {
+                one_in: encoded as u32,
+            },
+            _ => CheckStrategy::Never,
+        }
+    }
+
+    /// Returns whether this strategy checks the invariant of loop iteration `step`,
+    /// regardless of whether contracts are enabled.
+    pub fn checks(self, step: usize) -> bool {
+        match self {
+            CheckStrategy::Always => true,
+            CheckStrategy::PowersOfTwo => step & step.wrapping_sub(1) == 0,
+            CheckStrategy::Sampled { one_in } => SAMPLER.with(|state| {
+                let mut z = state.get();
+                z ^= z >> 12;
+                z ^= z << 25;
+                z ^= z >> 27;
+                state.set(z);
+                z.wrapping_mul(2685821657736338717) % one_in.max(1) as u64 == 0
+            }),
+            CheckStrategy::Never => false,
+        }
+    }
+}
+
+/// Selects the [CheckStrategy] for all threads. The default is [CheckStrategy::PowersOfTwo].
+pub fn set_check_strategy(strategy: CheckStrategy) {
+    CHECK_STRATEGY.store(strategy.encode(), Ordering::Relaxed);
+}
+
+/// Returns the current [CheckStrategy].
+pub fn check_strategy() -> CheckStrategy {
+    CheckStrategy::decode(CHECK_STRATEGY.load(Ordering::Relaxed))
+}
+
+/// Returns whether the invariant of loop iteration `step` should be checked under the current
+/// [CheckStrategy]. Always `false` when contracts are disabled in this build.
+pub fn should_check(step: usize) -> bool {
+    contract::ENABLED && check_strategy().checks(step)
+}
+
 /// Returns `true` iff `arr` is sorted in nondecreasing order.
 pub fn is_sorted<T: Ord>(arr: &[T]) -> bool {
     for i in 1..arr.len() {
@@ -87,7 +181,128 @@ pub fn le_segs<T: Ord>(arr1: &[T], arr2: &[T]) -> bool {
     }
 }
 
+/// Returns whether `a` and `b` contain the same elements with the same multiplicities.
+///
+/// Takes `O(n log n)` time; see [is_permutation_of_hashed] for an expected linear-time version.
+pub fn is_permutation_of<T: Ord>(a: &[T], b: &[T]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut a: Vec<&T> = a.iter().collect();
+    let mut b: Vec<&T> = b.iter().collect();
+    a.sort();
+    b.sort();
+    a == b
+}
+
+/// Same as [is_permutation_of] for hashable elements, in expected `O(n)` time.
+pub fn is_permutation_of_hashed<T: Hash + Eq>(a: &[T], b: &[T]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut counts: HashMap<&T, usize> = HashMap::with_capacity(a.len());
+    for x in a {
+        *counts.entry(x).or_insert(0) += 1;
+    }
+    for x in b {
+        match counts.get_mut(x) {
+            Some(0) | None => return false,
+            Some(c) => *c -= 1,
+        }
+    }
+    true
+}
+
+/// Returns whether `arr` is partitioned around `arr[mid]`: every element before it is
+/// less than or equal to it, and every element after it is greater than or equal to it.
+pub fn is_partitioned<T: Ord>(arr: &[T], mid: usize) -> bool {
+    mid < arr.len() && ge_seg(&arr[mid], &arr[..mid]) && le_seg(&arr[mid], &arr[mid + 1..])
+}
+
+/// Returns whether all elements satisfying `pred` come before all elements that do not.
+pub fn is_partitioned_by<T, P: Fn(&T) -> bool>(arr: &[T], pred: P) -> bool {
+    let split = arr.iter().take_while(|x| pred(x)).count();
+    arr[split..].iter().all(|x| !pred(x))
+}
+
+/// Returns whether `arr` is a max-heap: no element is greater than its parent `(i - 1) / 2`.
+pub fn is_heap<T: Ord>(arr: &[T]) -> bool {
+    is_dary_heap(arr, 2)
+}
+
+/// Returns whether `arr` is a binary heap in which no parent `p` of a child `c`
+/// satisfies `below(p, c)`. With `below` being `<`, this is a max-heap.
+pub fn is_heap_by<T, F: Fn(&T, &T) -> bool>(arr: &[T], below: F) -> bool {
+    is_dary_heap_by(arr, 2, below)
+}
+
+/// Returns whether `arr` is a `d`-ary max-heap: no element is greater than its parent
+/// `(i - 1) / d`. A binary heap has `d = 2`.
+pub fn is_dary_heap<T: Ord>(arr: &[T], d: usize) -> bool {
+    is_dary_heap_by(arr, d, |a, b| a < b)
+}
+
+/// Returns whether `arr` is a `d`-ary heap, where the parent of `i` is `(i - 1) / d`,
+/// in which no parent `p` of a child `c` satisfies `below(p, c)`.
+pub fn is_dary_heap_by<T, F: Fn(&T, &T) -> bool>(arr: &[T], d: usize, below: F) -> bool {
+    for i in 1..arr.len() {
+        let parent = (i - 1) / d;
+        if below(&arr[parent], &arr[i]) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Returns whether `arr` is a min-max heap: a binary heap in which every element on an even
+/// level (counting the root as level 0) is the least of its subtree, and every element on an
+/// odd level is the greatest of its subtree.
+pub fn is_min_max_heap<T: Ord>(arr: &[T]) -> bool {
+    // Comparing each element with its parent and grandparent suffices, by transitivity
+    for i in 1..arr.len() {
+        let parent = (i - 1) / 2;
+        let on_min_level = (i + 1).ilog2().is_multiple_of(2);
+        let parent_ok = if on_min_level {
+            arr[i] <= arr[parent]
+        } else {
+            arr[i] >= arr[parent]
+        };
+        if !parent_ok {
+            return false;
+        }
+        if parent > 0 {
+            let grandparent = (parent - 1) / 2;
+            let grandparent_ok = if on_min_level {
+                arr[i] >= arr[grandparent]
+            } else {
+                arr[i] <= arr[grandparent]
+            };
+            if !grandparent_ok {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// Returns whether `sorted` is the stable sort of `original` by `key`: it is sorted by `key`,
+/// and elements with equal keys appear in the same order as in `original`.
+pub fn is_stable_sorted_by<T: Eq, K: Ord, F: Fn(&T) -> K>(
+    original: &[T],
+    sorted: &[T],
+    key: F,
+) -> bool {
+    if original.len() != sorted.len() {
+        return false;
+    }
+    // `sort_by_key` is stable, so this is the one correct answer
+    let mut order: Vec<usize> = (0..original.len()).collect();
+    order.sort_by_key(|&i| key(&original[i]));
+    order.iter().zip(sorted).all(|(&i, x)| original[i] == *x)
+}
+
 #[cfg(test)]
+#[allow(clippy::useless_vec)]
 mod tests {
     use super::*;
 
@@ -434,4 +649,118 @@ mod tests {
         let a2 = vec![String::from("bee"), String::from("cat")];
         assert!(le_segs(&a1, &a2));
     }
+
+    // ---- is_permutation_of ------------------------------------------------
+
+    #[test]
+    fn is_permutation_of_cases() {
+        assert!(is_permutation_of(&[3, 1, 2, 1], &[1, 1, 2, 3]));
+        assert!(!is_permutation_of(&[3, 1, 2, 1], &[1, 2, 2, 3]));
+        assert!(!is_permutation_of(&[1, 2], &[1, 2, 2]));
+        assert!(is_permutation_of::<i32>(&[], &[]));
+    }
+
+    #[test]
+    fn is_permutation_of_hashed_agrees() {
+        let cases: [(&[i32], &[i32]); 4] = [
+            (&[3, 1, 2, 1], &[1, 1, 2, 3]),
+            (&[3, 1, 2, 1], &[1, 2, 2, 3]),
+            (&[1, 2], &[1, 2, 2]),
+            (&[], &[]),
+        ];
+        for (a, b) in cases {
+            assert_eq!(is_permutation_of_hashed(a, b), is_permutation_of(a, b));
+        }
+    }
+
+    // ---- is_partitioned ---------------------------------------------------
+
+    #[test]
+    fn is_partitioned_cases() {
+        assert!(is_partitioned(&[2, 1, 3, 5, 4], 2));
+        assert!(is_partitioned(&[3, 3, 3], 1));
+        assert!(!is_partitioned(&[2, 4, 3, 5, 1], 2));
+        assert!(!is_partitioned(&[1, 2], 2));
+    }
+
+    #[test]
+    fn is_partitioned_by_cases() {
+        assert!(is_partitioned_by(&[1, 2, 5, 6], |&y| y < 3));
+        assert!(is_partitioned_by(&[5, 6], |&y| y < 3));
+        assert!(!is_partitioned_by(&[1, 5, 2, 6], |&y| y < 3));
+    }
+
+    // ---- is_heap ----------------------------------------------------------
+
+    #[test]
+    fn is_heap_cases() {
+        assert!(is_heap(&[9, 5, 8, 1, 4, 7]));
+        assert!(!is_heap(&[9, 5, 8, 6]));
+        assert!(is_heap::<i32>(&[]));
+        assert!(is_heap_by(&[1, 4, 2, 5], |a, b| a > b));
+        assert!(!is_heap_by(&[9, 5, 8, 1], |a, b| a > b));
+        // Children of 0 are 1, 2, 3
+        assert!(is_dary_heap(&[9, 5, 8, 7, 1, 2, 3, 4], 3));
+        assert!(!is_dary_heap(&[9, 5, 8, 7, 6], 3));
+        assert!(is_dary_heap_by(&[9, 5, 8, 7, 1], 3, |a, b| a < b));
+        assert!(!is_dary_heap_by(&[9, 5, 8, 7, 6], 3, |a, b| a < b));
+    }
+
+    #[test]
+    fn is_min_max_heap_cases() {
+        assert!(is_min_max_heap::<i32>(&[]));
+        assert!(is_min_max_heap(&[1, 9, 8, 2, 3, 4, 5]));
+        // 7 is on a min level but greater than its parent 6
+        assert!(!is_min_max_heap(&[1, 9, 6, 2, 3, 7]));
+        // 0 is on a min level but less than its grandparent 1
+        assert!(!is_min_max_heap(&[1, 9, 8, 0]));
+        assert!(!is_min_max_heap(&[5, 4]));
+    }
+
+    // ---- is_stable_sorted_by ----------------------------------------------
+
+    #[test]
+    fn is_stable_sorted_by_cases() {
+        let original = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+        let stable = [(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')];
+        let unstable = [(1, 'd'), (1, 'b'), (2, 'a'), (2, 'c')];
+        assert!(is_stable_sorted_by(&original, &stable, |p| p.0));
+        assert!(!is_stable_sorted_by(&original, &unstable, |p| p.0));
+        assert!(!is_stable_sorted_by(&original, &original, |p| p.0));
+    }
+
+    // ---- check strategy ---------------------------------------------------
+
+    #[test]
+    fn check_strategies() {
+        // Exercises the strategies directly: the global one is shared with concurrent tests
+        let sampled = CheckStrategy::Sampled { one_in: 4 };
+        let hits = (0..4000).filter(|&i| sampled.checks(i)).count();
+        assert!((500..1500).contains(&hits));
+
+        assert!((0..100).all(|i| !CheckStrategy::Never.checks(i)));
+        assert!((0..100).all(|i| CheckStrategy::Always.checks(i)));
+
+        let checked: Vec<usize> = (0..20)
+            .filter(|&i| CheckStrategy::PowersOfTwo.checks(i))
+            .collect();
+        assert_eq!(checked, vec![0, 1, 2, 4, 8, 16]);
+    }
+
+    #[test]
+    fn check_strategy_encoding_round_trips() {
+        for strategy in [
+            CheckStrategy::Always,
+            CheckStrategy::PowersOfTwo,
+            CheckStrategy::Sampled { one_in: 4 },
+            CheckStrategy::Sampled { one_in: u32::MAX },
+            CheckStrategy::Never,
+        ] {
+            assert_eq!(CheckStrategy::decode(strategy.encode()), strategy);
+        }
+        assert_eq!(
+            CheckStrategy::decode(CheckStrategy::Sampled { one_in: 0 }.encode()),
+            CheckStrategy::Sampled { one_in: 1 }
+        );
+    }
 }
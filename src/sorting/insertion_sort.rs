@@ -7,8 +7,10 @@ Implements:
 - [sort]
 */
 
-use crate::sorting::helper::is_sorted;
-use contracts::debug_ensures;
+use crate::contract::loop_invariant;
+#[cfg(any(debug_assertions, feature = "checked"))]
+use crate::sorting::helper::is_permutation_of;
+use crate::sorting::helper::{is_sorted, should_check};
 
 /**
 Insertion Sort
@@ -23,11 +25,18 @@ Best-case Time Complexity: `O(n)`
 Space Complexity: `O(1)`
 Stable?: Yes
 */
-#[debug_ensures(is_sorted(arr))]
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::ensures(is_sorted(arr)),
+    contracts::ensures(is_permutation_of(arr, &old(arr.to_vec())))
+)]
 pub fn sort<T: Ord + Clone>(arr: &mut [T]) {
     let n = arr.len();
     for i in 1..n {
-        debug_assert!(is_sorted(&arr[0..i]));
+        let check = should_check(i);
+        if check {
+            loop_invariant!(is_sorted(&arr[0..i]));
+        }
         let key = arr[i].clone();
         let mut j = i;
         while j > 0 && arr[j - 1] > key {
@@ -35,7 +44,9 @@ pub fn sort<T: Ord + Clone>(arr: &mut [T]) {
             j -= 1;
         }
         arr[j] = key;
-        debug_assert!(is_sorted(&arr[0..=i]));
+        if check {
+            loop_invariant!(is_sorted(&arr[0..=i]));
+        }
     }
 }
 
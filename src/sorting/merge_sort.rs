@@ -7,15 +7,20 @@ Implements:
 - [sort]
 */
 
-use crate::sorting::helper::is_sorted;
-use contracts::{debug_ensures, debug_requires};
+use crate::contract::loop_invariant;
+#[cfg(any(debug_assertions, feature = "checked"))]
+use crate::sorting::helper::is_permutation_of;
+use crate::sorting::helper::{is_sorted, should_check};
 
 /// Merges sorted arrays `a` and `b` into a single sorted array `result`
-#[debug_requires(result.len() >= a.len() + b.len())]
-#[debug_requires(is_sorted(a))]
-#[debug_requires(is_sorted(b))]
-#[debug_ensures(is_sorted(result))]
-fn merge<T: Ord + Clone>(a: &[T], b: &[T], result: &mut [T]) {
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::requires(result.len() >= a.len() + b.len()),
+    contracts::requires(is_sorted(a)),
+    contracts::requires(is_sorted(b)),
+    contracts::ensures(is_sorted(result))
+)]
+pub(crate) fn merge<T: Ord + Clone>(a: &[T], b: &[T], result: &mut [T]) {
     let mut i: usize = 0;
     let mut j: usize = 0;
     while i < a.len() || j < b.len() {
@@ -26,9 +31,31 @@ fn merge<T: Ord + Clone>(a: &[T], b: &[T], result: &mut [T]) {
             result[i + j] = a[i].clone();
             i += 1;
         }
-        debug_assert!(is_sorted(&result[..i + j]));
+        if should_check(i + j) {
+            loop_invariant!(is_sorted(&result[..i + j]));
+        }
     }
-    debug_assert!(i == a.len() && j == b.len());
+    loop_invariant!(i == a.len() && j == b.len());
+}
+
+/// Sorts both halves recursively, then merges them
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::ensures(is_sorted(arr))
+)]
+fn sort_recursive<T: Ord + Clone>(arr: &mut [T]) {
+    if arr.len() <= 1 {
+        return;
+    }
+
+    let mid = arr.len() / 2;
+    sort_recursive(&mut arr[..mid]);
+    loop_invariant!(is_sorted(&arr[..mid]));
+    sort_recursive(&mut arr[mid..]);
+    loop_invariant!(is_sorted(&arr[mid..]));
+    let mut result = arr.to_vec();
+    merge(&arr[..mid], &arr[mid..], &mut result);
+    arr.clone_from_slice(&result);
 }
 
 /**
@@ -44,20 +71,13 @@ Best-case Time Complexity: `O(n log n)`
 Space Complexity: `O(n)`
 Stable?: Yes
 */
-#[debug_ensures(is_sorted(arr))]
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::ensures(is_sorted(arr)),
+    contracts::ensures(is_permutation_of(arr, &old(arr.to_vec())))
+)]
 pub fn sort<T: Ord + Clone>(arr: &mut [T]) {
-    if arr.len() <= 1 {
-        return;
-    }
-
-    let mid = arr.len() / 2;
-    sort(&mut arr[..mid]);
-    debug_assert!(is_sorted(&arr[..mid]));
-    sort(&mut arr[mid..]);
-    debug_assert!(is_sorted(&arr[mid..]));
-    let mut result = arr.to_vec();
-    merge(&arr[..mid], &arr[mid..], &mut result);
-    arr.clone_from_slice(&result);
+    sort_recursive(arr);
 }
 
 #[cfg(test)]
@@ -1,9 +1,15 @@
+pub mod auto;
 pub mod bubble_sort;
 pub mod heap_sort;
-mod helper;
+pub mod helper;
 pub mod insertion_sort;
 pub mod merge_sort;
+pub mod natural_merge_sort;
+pub(crate) mod prop_test;
 pub mod quick_sort;
 pub mod selection_sort;
 pub mod shell_sort_a003462;
 mod sort_test_helper;
+pub mod three_way_quick_sort;
+
+pub use auto::sort as sort_auto;
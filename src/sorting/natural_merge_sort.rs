@@ -0,0 +1,155 @@
+/*!
+Sorting Algorithms
+
+Natural Merge Sort
+
+Implements:
+- [sort]
+*/
+
+use crate::contract::loop_invariant;
+#[cfg(any(debug_assertions, feature = "checked"))]
+use crate::sorting::helper::is_permutation_of;
+use crate::sorting::helper::is_sorted;
+use crate::sorting::merge_sort::merge;
+
+/// Finds the maximal run starting at `start`: returns its (exclusive) end, and whether it is
+/// strictly descending rather than nondecreasing.
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::requires(start < arr.len()),
+    contracts::ensures(start < ret.0 && ret.0 <= arr.len())
+)]
+pub(crate) fn run_end<T: Ord>(arr: &[T], start: usize) -> (usize, bool) {
+    let n = arr.len();
+    let mut end = start + 1;
+    let descending = end < n && arr[end] < arr[end - 1];
+    if descending {
+        while end < n && arr[end] < arr[end - 1] {
+            end += 1;
+        }
+    } else {
+        while end < n && arr[end - 1] <= arr[end] {
+            end += 1;
+        }
+    }
+    (end, descending)
+}
+
+/// Finds the maximal run starting at `start` and returns its (exclusive) end.
+/// A strictly descending run is reversed in place, so every run is nondecreasing.
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::requires(start < arr.len()),
+    contracts::ensures(start < ret && ret <= arr.len()),
+    contracts::ensures(is_sorted(&arr[start..ret]))
+)]
+fn next_run<T: Ord>(arr: &mut [T], start: usize) -> usize {
+    let (end, descending) = run_end(arr, start);
+    if descending {
+        // Strictly descending, so reversing it cannot reorder equal elements
+        arr[start..end].reverse();
+    }
+    end
+}
+
+/**
+Natural Merge Sort
+
+Given an array `arr` containing `n` elements of type `T`, sort it in-place in nondecreasing order.
+
+The array is first split into its maximal nondecreasing (or strictly descending) runs,
+which are then merged pairwise bottom-up. An input made of `r` runs only needs
+`ceil(log2(r))` merge passes, so nearly sorted inputs are sorted in close to linear time.
+
+`T` must implement [Ord] and [Clone].
+
+Worst-case Time Complexity: `O(n log n)`
+Average-case Time Complexity: `O(n log n)`
+Best-case Time Complexity: `O(n)`
+Space Complexity: `O(n)`
+Stable?: Yes
+*/
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::ensures(is_sorted(arr)),
+    contracts::ensures(is_permutation_of(arr, &old(arr.to_vec())))
+)]
+pub fn sort<T: Ord + Clone>(arr: &mut [T]) {
+    let n = arr.len();
+    if n <= 1 {
+        return;
+    }
+
+    // bounds[k]..bounds[k + 1] is the k-th run
+    let mut bounds = vec![0];
+    let mut start = 0;
+    while start < n {
+        start = next_run(arr, start);
+        bounds.push(start);
+    }
+
+    let mut buffer = arr.to_vec();
+    while bounds.len() > 2 {
+        // Loop Invariants
+        // Every run is sorted and the runs cover the whole array.
+        loop_invariant!(bounds[0] == 0 && bounds[bounds.len() - 1] == n);
+        loop_invariant!(bounds.windows(2).all(|w| is_sorted(&arr[w[0]..w[1]])));
+
+        let mut merged = Vec::with_capacity(bounds.len() / 2 + 2);
+        merged.push(0);
+        let mut k = 0;
+        while k + 2 < bounds.len() {
+            let (lo, mid, hi) = (bounds[k], bounds[k + 1], bounds[k + 2]);
+            merge(&arr[lo..mid], &arr[mid..hi], &mut buffer[lo..hi]);
+            arr[lo..hi].clone_from_slice(&buffer[lo..hi]);
+            merged.push(hi);
+            k += 2;
+        }
+        // An odd run out is carried over to the next pass unchanged
+        if k + 2 == bounds.len() {
+            merged.push(bounds[k + 1]);
+        }
+        bounds = merged;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sorting::sort_test_helper::test_sort;
+
+    #[test]
+    fn test_natural_merge_sort() {
+        test_sort(sort);
+    }
+
+    #[test]
+    fn test_natural_merge_sort_is_stable() {
+        #[derive(Clone, Debug)]
+        struct Keyed(i32, usize);
+        impl PartialEq for Keyed {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+        impl Eq for Keyed {}
+        impl PartialOrd for Keyed {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for Keyed {
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                self.0.cmp(&other.0)
+            }
+        }
+
+        let keys = [3, 1, 2, 1, 3, 0, 2, 2, 1, 0, 3, 3, 1];
+        let mut arr: Vec<Keyed> = keys.iter().enumerate().map(|(i, &k)| Keyed(k, i)).collect();
+        sort(&mut arr);
+        for w in arr.windows(2) {
+            assert!(w[0].0 < w[1].0 || (w[0].0 == w[1].0 && w[0].1 < w[1].1));
+        }
+    }
+}
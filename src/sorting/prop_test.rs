@@ -0,0 +1,367 @@
+#![cfg(test)]
+/*!
+Sorting Algorithms — Property-Based Testing
+
+A small, dependency-free property-based testing framework:
+- [Rng]: a seeded xorshift64* generator, so every case is reproducible from its seed
+- [Shape]: the input distributions the generator draws from
+- [shrink]: greedily reduces a failing input to a minimal counterexample
+- [check_sort]: differentially checks a sort against [slice::sort]
+
+A failure is either a wrong result or a panic inside the sort, such as a violated
+`debug_ensures` contract or loop invariant. Either way, the report contains the seed,
+the original input, the shrunk input and the failure message.
+*/
+
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+type IntegerSortingAlgorithm = fn(arr: &mut [isize]);
+
+/// Seeded xorshift64* pseudo-random number generator
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // xorshift gets stuck at zero
+        Rng(if seed == 0 {
+            0x9E37_79B9_7F4A_7C15
+        } else {
+            seed
+        })
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut z = self.0;
+        z ^= z >> 12;
+        z ^= z << 25;
+        z ^= z >> 27;
+        self.0 = z;
+        z.wrapping_mul(2685821657736338717)
+    }
+
+    /// Uniform in `[0, n)`; `n` must be positive
+    #[cfg_attr(
+        any(debug_assertions, feature = "checked"),
+        contracts::requires(n > 0)
+    )]
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+
+    /// Uniform in `[-range, range]`
+    pub fn symmetric(&mut self, range: isize) -> isize {
+        let span = 2 * (range as u64) + 1;
+        (self.below(span) as i128 - range as i128) as isize
+    }
+}
+
+/// The input distributions drawn by [generate]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Shape {
+    /// Independent uniform values over a wide range
+    Uniform,
+    /// Values from a domain of at most 4 elements
+    FewDistinct,
+    /// A sorted sequence with a few random swaps
+    NearlySorted,
+    /// A descending sequence
+    Reversed,
+    /// Concatenated sorted runs
+    Runs,
+    /// Mostly the extreme values `isize::MIN`, `isize::MAX`, `0`
+    Extremes,
+}
+
+pub const SHAPES: [Shape; 6] = [
+    Shape::Uniform,
+    Shape::FewDistinct,
+    Shape::NearlySorted,
+    Shape::Reversed,
+    Shape::Runs,
+    Shape::Extremes,
+];
+
+/// Generates an input of length at most `max_len` with the given shape
+pub fn generate(rng: &mut Rng, shape: Shape, max_len: usize) -> Vec<isize> {
+    let len = rng.below(max_len as u64 + 1) as usize;
+    match shape {
+        Shape::Uniform => (0..len).map(|_| rng.symmetric(1_000_000)).collect(),
+        Shape::FewDistinct => {
+            let k = 1 + rng.below(4) as isize;
+            (0..len).map(|_| rng.symmetric(k / 2)).collect()
+        }
+        Shape::NearlySorted => {
+            let mut v: Vec<isize> = (0..len as isize).collect();
+            for _ in 0..=len / 16 {
+                if len > 1 {
+                    let i = rng.below(len as u64) as usize;
+                    let j = rng.below(len as u64) as usize;
+                    v.swap(i, j);
+                }
+            }
+            v
+        }
+        Shape::Reversed => (0..len as isize).rev().collect(),
+        Shape::Runs => {
+            let mut v = Vec::with_capacity(len);
+            while v.len() < len {
+                let run = 1 + rng.below(8) as usize;
+                let mut x = rng.symmetric(100);
+                for _ in 0..run.min(len - v.len()) {
+                    v.push(x);
+                    x += rng.below(3) as isize;
+                }
+            }
+            v
+        }
+        Shape::Extremes => (0..len)
+            .map(|_| match rng.below(4) {
+                0 => isize::MIN,
+                1 => isize::MAX,
+                2 => 0,
+                _ => rng.symmetric(2),
+            })
+            .collect(),
+    }
+}
+
+/// Candidate simplifications of `input`, most aggressive first:
+/// dropping halves, dropping single elements, then moving single values towards `0`.
+pub fn shrink_candidates(input: &[isize]) -> Vec<Vec<isize>> {
+    let n = input.len();
+    let mut out = Vec::new();
+
+    let mut chunk = n / 2;
+    while chunk >= 1 {
+        let mut start = 0;
+        while start < n {
+            let end = (start + chunk).min(n);
+            let mut v = input[..start].to_vec();
+            v.extend_from_slice(&input[end..]);
+            out.push(v);
+            start += chunk;
+        }
+        chunk /= 2;
+    }
+
+    for i in 0..n {
+        let x = input[i];
+        for y in [0, x / 2, x - x.signum()] {
+            if y != x && y.unsigned_abs() < x.unsigned_abs() {
+                let mut v = input.to_vec();
+                v[i] = y;
+                out.push(v);
+            }
+        }
+    }
+    out
+}
+
+/// Greedily shrinks `input` while `fails` keeps returning a failure.
+/// Returns the minimal input found and its failure message.
+pub fn shrink<F>(mut input: Vec<isize>, mut message: String, fails: F) -> (Vec<isize>, String)
+where
+    F: Fn(&[isize]) -> Option<String>,
+{
+    'outer: loop {
+        for candidate in shrink_candidates(&input) {
+            if let Some(msg) = fails(&candidate) {
+                input = candidate;
+                message = msg;
+                continue 'outer;
+            }
+        }
+        return (input, message);
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else {
+        String::from("<non-string panic payload>")
+    }
+}
+
+/// Runs `sort` on a copy of `input` and compares against [slice::sort].
+/// Returns a description of the failure, if any.
+pub fn differential(sort: IntegerSortingAlgorithm, input: &[isize]) -> Option<String> {
+    let mut expected = input.to_vec();
+    expected.sort();
+
+    let mut got = input.to_vec();
+    match panic::catch_unwind(AssertUnwindSafe(|| sort(&mut got))) {
+        Err(payload) => Some(format!("panicked: {}", panic_message(payload.as_ref()))),
+        Ok(()) if got != expected => Some(format!("got {:?}, expected {:?}", got, expected)),
+        Ok(()) => None,
+    }
+}
+
+/// Settings for [check_sort]
+#[derive(Copy, Clone, Debug)]
+pub struct Config {
+    pub seed: u64,
+    pub cases: usize,
+    pub max_len: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            seed: 0x5AFE_D5A0,
+            cases: 120,
+            max_len: 48,
+        }
+    }
+}
+
+/// A reproducible, shrunk counterexample
+#[derive(Clone, Debug)]
+pub struct Failure {
+    pub seed: u64,
+    pub case: usize,
+    pub shape: Shape,
+    pub original: Vec<isize>,
+    pub shrunk: Vec<isize>,
+    pub message: String,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "property failed (seed {:#x}, case {}, shape {:?})",
+            self.seed, self.case, self.shape
+        )?;
+        writeln!(f, "  original: {:?}", self.original)?;
+        writeln!(f, "  shrunk:   {:?}", self.shrunk)?;
+        write!(f, "  failure:  {}", self.message)
+    }
+}
+
+/// Searches for an input on which `sort` disagrees with [slice::sort] or panics.
+pub fn find_failure(sort: IntegerSortingAlgorithm, config: Config) -> Option<Failure> {
+    let mut rng = Rng::new(config.seed);
+    for case in 0..config.cases {
+        let shape = SHAPES[case % SHAPES.len()];
+        let input = generate(&mut rng, shape, config.max_len);
+        if let Some(message) = differential(sort, &input) {
+            let (shrunk, message) = shrink(input.clone(), message, |v| differential(sort, v));
+            return Some(Failure {
+                seed: config.seed,
+                case,
+                shape,
+                original: input,
+                shrunk,
+                message,
+            });
+        }
+    }
+    None
+}
+
+/// Panics with a report of the shrunk counterexample if `sort` fails any generated case.
+pub fn check_sort(sort: IntegerSortingAlgorithm) {
+    if let Some(failure) = find_failure(sort, Config::default()) {
+        panic!("{}", failure);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{ENABLED, Kind, catch};
+    #[cfg(any(debug_assertions, feature = "checked"))]
+    use crate::sorting::helper::{is_permutation_of, is_sorted};
+
+    #[test]
+    fn rng_is_reproducible() {
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+        assert_ne!(Rng::new(0).next_u64(), 0);
+    }
+
+    #[test]
+    fn below_zero_violates_precondition() {
+        // Without contracts, the remainder by zero panics on its own
+        if ENABLED {
+            let v = catch(|| Rng::new(1).below(0)).unwrap_err();
+            assert_eq!(v.kind, Kind::Precondition);
+        }
+    }
+
+    #[test]
+    fn generate_respects_max_len() {
+        let mut rng = Rng::new(1);
+        for shape in SHAPES {
+            for _ in 0..20 {
+                assert!(generate(&mut rng, shape, 10).len() <= 10);
+            }
+        }
+    }
+
+    #[test]
+    fn shrinks_wrong_result_to_minimal_input() {
+        // Forgets to look at the last element
+        fn off_by_one(arr: &mut [isize]) {
+            let n = arr.len();
+            if n > 1 {
+                arr[..n - 1].sort();
+            }
+        }
+        let failure = find_failure(off_by_one, Config::default()).unwrap();
+        assert_eq!(failure.shrunk.len(), 2);
+        assert!(failure.shrunk[0] > failure.shrunk[1]);
+    }
+
+    #[test]
+    fn shrinks_contract_violation_to_minimal_input() {
+        // Silently drops duplicates, caught by its own postcondition
+        #[cfg_attr(
+            any(debug_assertions, feature = "checked"),
+            contracts::ensures(arr.windows(2).all(|w| w[0] < w[1]))
+        )]
+        fn dedup_sort(arr: &mut [isize]) {
+            arr.sort();
+        }
+        if !ENABLED {
+            return;
+        }
+        let failure = find_failure(dedup_sort, Config::default()).unwrap();
+        assert_eq!(failure.shrunk.len(), 2);
+        assert_eq!(failure.shrunk[0], failure.shrunk[1]);
+        assert!(
+            failure
+                .message
+                .contains("Post-condition of dedup_sort violated")
+        );
+    }
+
+    #[test]
+    fn permutation_postcondition_catches_overwriting_sort() {
+        // Sorted, but not a permutation of its input
+        #[cfg_attr(
+            any(debug_assertions, feature = "checked"),
+            contracts::ensures(is_sorted(arr)),
+            contracts::ensures(is_permutation_of(arr, &old(arr.to_vec())))
+        )]
+        fn flatten(arr: &mut [isize]) {
+            if let Some(&first) = arr.first() {
+                arr.fill(first);
+            }
+        }
+        if !ENABLED {
+            return;
+        }
+        let failure = find_failure(flatten, Config::default()).unwrap();
+        assert_eq!(failure.shrunk.len(), 2);
+        assert!(failure.message.contains("is_permutation_of"));
+    }
+}
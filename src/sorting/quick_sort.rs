@@ -7,14 +7,19 @@ Implements:
 - [sort]
 */
 
-use crate::sorting::helper::{ge_seg, is_sorted, le_seg};
-use contracts::{debug_ensures, debug_requires};
+use crate::contract::loop_invariant;
+use crate::sorting::helper::{ge_seg, le_seg, should_check};
+#[cfg(any(debug_assertions, feature = "checked"))]
+use crate::sorting::helper::{is_permutation_of, is_sorted};
 
 /// Partitions by `pi`
-#[debug_requires(pi <= arr.len())]
-#[debug_ensures(ret < arr.len())]
-#[debug_ensures(ge_seg(&arr[ret], &arr[..ret]))]
-#[debug_ensures(le_seg(&arr[ret], &arr[ret..]))]
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::requires(pi <= arr.len()),
+    contracts::ensures(ret < arr.len()),
+    contracts::ensures(ge_seg(&arr[ret], &arr[..ret])),
+    contracts::ensures(le_seg(&arr[ret], &arr[ret..]))
+)]
 fn partition<T: Ord + Clone>(arr: &mut [T], pi: usize) -> usize {
     let pivot = arr[pi].clone();
     arr.swap(0, pi);
@@ -23,8 +28,10 @@ fn partition<T: Ord + Clone>(arr: &mut [T], pi: usize) -> usize {
     let mut right = arr.len();
 
     while left < right {
-        debug_assert!(ge_seg(&pivot, &arr[1..left]));
-        debug_assert!(le_seg(&pivot, &arr[right..]));
+        if should_check(left + arr.len() - right) {
+            loop_invariant!(ge_seg(&pivot, &arr[1..left]));
+            loop_invariant!(le_seg(&pivot, &arr[right..]));
+        }
 
         let ok = arr[left] <= pivot;
         if ok {
@@ -35,14 +42,31 @@ fn partition<T: Ord + Clone>(arr: &mut [T], pi: usize) -> usize {
         }
     }
 
-    debug_assert!(ge_seg(&pivot, &arr[1..left]));
-    debug_assert!(le_seg(&pivot, &arr[right..]));
-    debug_assert!(left == right);
+    loop_invariant!(ge_seg(&pivot, &arr[1..left]));
+    loop_invariant!(le_seg(&pivot, &arr[right..]));
+    loop_invariant!(left == right);
 
     arr.swap(0, left - 1);
     left - 1
 }
 
+/// Partitions around the middle element and recurses on both sides of the pivot.
+/// Kept apart from [sort] so that the permutation postcondition is checked only once.
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::ensures(is_sorted(arr))
+)]
+fn sort_recursive<T: Ord + Clone>(arr: &mut [T]) {
+    if arr.len() <= 1 {
+        return;
+    }
+
+    let pi = arr.len() / 2;
+    let mid = partition(arr, pi);
+    sort_recursive(&mut arr[..mid]);
+    sort_recursive(&mut arr[mid + 1..]);
+}
+
 /**
 Quick Sort
 
@@ -56,16 +80,13 @@ Best-case Time Complexity: `O(n log n)`
 Space Complexity: `O(1)`
 Stable?: No
 */
-#[debug_ensures(is_sorted(arr))]
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::ensures(is_sorted(arr)),
+    contracts::ensures(is_permutation_of(arr, &old(arr.to_vec())))
+)]
 pub fn sort<T: Ord + Clone>(arr: &mut [T]) {
-    if arr.len() <= 1 {
-        return;
-    }
-
-    let pi = arr.len() / 2;
-    let mid = partition(arr, pi);
-    sort(&mut arr[..mid]);
-    sort(&mut arr[mid + 1..]);
+    sort_recursive(arr);
 }
 
 #[cfg(test)]
@@ -7,15 +7,18 @@ Implements:
 - [sort]
 */
 
-use crate::sorting::helper::{is_sorted, le_seg, le_segs};
-use contracts::debug_ensures;
+use crate::contract::Snapshot;
+use crate::contract::loop_invariant;
+#[cfg(any(debug_assertions, feature = "checked"))]
+use crate::sorting::helper::is_permutation_of;
+use crate::sorting::helper::{is_sorted, le_segs, should_check};
 
 /**
 Selection Sort
 
 Given an array `arr` containing `n` elements of type `T`, sort it in-place in nondecreasing order.
 
-`T` must implement [Ord].
+`T` must implement [Ord], and [Clone] in builds that check contracts ([Snapshot]).
 
 Worst-case Time Complexity: `O(n^2)`
 Average-case Time Complexity: `O(n^2)`
@@ -23,8 +26,12 @@ Best-case Time Complexity: `O(n^2)`
 Space Complexity: `O(1)`
 Stable?: No
 */
-#[debug_ensures(is_sorted(arr))]
-pub fn sort<T: Ord>(arr: &mut [T]) {
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::ensures(is_sorted(arr)),
+    contracts::ensures(is_permutation_of(arr, &old(arr.to_vec())))
+)]
+pub fn sort<T: Ord + Snapshot>(arr: &mut [T]) {
     let n = arr.len();
     for i in 0..n {
         // k is the index of a minimal element in [i+1, n)
@@ -35,13 +42,15 @@ pub fn sort<T: Ord>(arr: &mut [T]) {
             }
 
             // Loop Invariants
-            // debug_assert!(le_seg(&arr[k], &arr[i..=j]))
+            // loop_invariant!(le_seg(&arr[k], &arr[i..=j]))
         }
         arr.swap(i, k);
 
         // Loop Invariants
-        debug_assert!(is_sorted(&arr[..=i]));
-        debug_assert!(le_segs(&arr[..=i], &arr[i + 1..]));
+        if should_check(i) {
+            loop_invariant!(is_sorted(&arr[..=i]));
+            loop_invariant!(le_segs(&arr[..=i], &arr[i + 1..]));
+        }
     }
 }
 
@@ -7,8 +7,8 @@ Implements:
 - [sort]
 */
 
-use crate::sorting::helper::is_sorted;
-use contracts::debug_ensures;
+#[cfg(any(debug_assertions, feature = "checked"))]
+use crate::sorting::helper::{is_permutation_of, is_sorted};
 
 /**
 Shell Sort with gap sequence A003462
@@ -23,7 +23,11 @@ Best-case Time Complexity: `O(n log n)`
 Space Complexity: `O(1)`
 Stable?: No
 */
-#[debug_ensures(is_sorted(arr))]
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::ensures(is_sorted(arr)),
+    contracts::ensures(is_permutation_of(arr, &old(arr.to_vec())))
+)]
 pub fn sort<T: Ord + Clone>(arr: &mut [T]) {
     let n = arr.len();
     let mut k = 1;
@@ -1,4 +1,7 @@
 #![cfg(test)]
+use crate::sorting::helper::is_permutation_of_hashed;
+use crate::sorting::prop_test::{Rng, check_sort};
+
 type IntegerSortingAlgorithm = fn(arr: &mut [isize]);
 
 // ---- Internal helpers ----
@@ -13,7 +16,13 @@ fn run_and_compare_full(sort: IntegerSortingAlgorithm, arr: &mut [isize]) {
     // Run the user's sort.
     sort(arr);
 
-    // Check: equal to std sort, nondecreasing, and idempotent.
+    // Check: a permutation of the input, equal to std sort, nondecreasing, and idempotent.
+    assert!(
+        is_permutation_of_hashed(arr, &original),
+        "Sorting is not a permutation.\n  input:  {:?}\n  got:    {:?}",
+        original,
+        arr
+    );
     assert_eq!(
         arr,
         &expected[..],
@@ -53,6 +62,12 @@ fn run_and_compare_partial(sort: IntegerSortingAlgorithm, arr: &mut [isize], lo:
     // Sort only the subslice
     sort(&mut arr[lo..hi]);
 
+    // The subslice must be rearranged, not rewritten.
+    assert!(
+        is_permutation_of_hashed(&arr[lo..hi], &original[lo..hi]),
+        "Partial sort is not a permutation of the subslice"
+    );
+
     // Everything outside [lo,hi) should remain identical to the original.
     assert_eq!(
         &arr[..lo],
@@ -88,22 +103,9 @@ fn run_and_compare_partial(sort: IntegerSortingAlgorithm, arr: &mut [isize], lo:
     assert_eq!(again, expected, "Partial sort not idempotent");
 }
 
-fn pseudo_random_vec(len: usize, mut seed: u64, range: isize) -> Vec<isize> {
-    fn xorshift64star(x: &mut u64) -> u64 {
-        let mut z = *x;
-        z ^= z >> 12;
-        z ^= z << 25;
-        z ^= z >> 27;
-        *x = z;
-        z.wrapping_mul(2685821657736338717)
-    }
-    let mut out = Vec::with_capacity(len);
-    for _ in 0..len {
-        let r = xorshift64star(&mut seed);
-        let v = (r % ((2 * (range as u64)) + 1)) as i128 - (range as i128);
-        out.push(v as isize);
-    }
-    out
+fn pseudo_random_vec(len: usize, seed: u64, range: isize) -> Vec<isize> {
+    let mut rng = Rng::new(seed);
+    (0..len).map(|_| rng.symmetric(range)).collect()
 }
 
 // ---- Individual scenario tests ----
@@ -241,8 +243,8 @@ fn test_many_small_arrays(sort: IntegerSortingAlgorithm) {
         // Iterate all 3^len assignments.
         let total = 3usize.pow(len as u32);
         for mut code in 0..total {
-            for i in 0..len {
-                buf[i] = domain[code % 3];
+            for slot in buf.iter_mut().take(len) {
+                *slot = domain[code % 3];
                 code /= 3;
             }
             let mut arr = buf[..len].to_vec();
@@ -284,4 +286,7 @@ pub fn test_sort(sort: IntegerSortingAlgorithm) {
 
     // Many small arrays over a tiny domain
     test_many_small_arrays(sort);
+
+    // Generated inputs, differentially checked and shrunk on failure
+    check_sort(sort);
 }
@@ -0,0 +1,119 @@
+/*!
+Sorting Algorithms
+
+Three-Way Quick Sort
+
+Implements:
+- [sort]
+*/
+
+use crate::contract::loop_invariant;
+use crate::sorting::helper::{ge_seg, gt_seg, le_seg, lt_seg, should_check};
+#[cfg(any(debug_assertions, feature = "checked"))]
+use crate::sorting::helper::{is_permutation_of, is_sorted};
+use std::cmp::Ordering;
+
+/// Partitions by `pi` into elements less than, equal to and greater than the pivot.
+/// Returns `(lt, gt)` such that `arr[lt..gt]` are exactly the elements equal to the pivot.
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::requires(pi < arr.len()),
+    contracts::ensures(ret.0 < ret.1 && ret.1 <= arr.len()),
+    contracts::ensures(gt_seg(&arr[ret.0], &arr[..ret.0])),
+    contracts::ensures(ge_seg(&arr[ret.0], &arr[ret.0..ret.1])),
+    contracts::ensures(le_seg(&arr[ret.0], &arr[ret.0..ret.1])),
+    contracts::ensures(lt_seg(&arr[ret.0], &arr[ret.1..]))
+)]
+fn partition<T: Ord + Clone>(arr: &mut [T], pi: usize) -> (usize, usize) {
+    let pivot = arr[pi].clone();
+
+    let mut lt = 0;
+    let mut i = 0;
+    let mut gt = arr.len();
+
+    while i < gt {
+        if should_check(i + arr.len() - gt) {
+            loop_invariant!(gt_seg(&pivot, &arr[..lt]));
+            loop_invariant!(ge_seg(&pivot, &arr[lt..i]));
+            loop_invariant!(le_seg(&pivot, &arr[lt..i]));
+            loop_invariant!(lt_seg(&pivot, &arr[gt..]));
+        }
+
+        match arr[i].cmp(&pivot) {
+            Ordering::Less => {
+                arr.swap(lt, i);
+                lt += 1;
+                i += 1;
+            }
+            Ordering::Greater => {
+                gt -= 1;
+                arr.swap(i, gt);
+            }
+            Ordering::Equal => {
+                i += 1;
+            }
+        }
+    }
+
+    loop_invariant!(i == gt);
+    (lt, gt)
+}
+
+/// Recurses on the smaller of the parts less than and greater than the pivot and loops on the
+/// larger one, skipping the elements equal to it. The stack is then `O(log n)` deep.
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::ensures(is_sorted(arr))
+)]
+fn sort_recursive<T: Ord + Clone>(arr: &mut [T]) {
+    let mut rest = &mut *arr;
+    while rest.len() > 1 {
+        let pi = rest.len() / 2;
+        let (lt, gt) = partition(rest, pi);
+        let (less, equal_greater) = std::mem::take(&mut rest).split_at_mut(lt);
+        let greater = &mut equal_greater[gt - lt..];
+        if less.len() <= greater.len() {
+            sort_recursive(less);
+            rest = greater;
+        } else {
+            sort_recursive(greater);
+            rest = less;
+        }
+    }
+}
+
+/**
+Three-Way Quick Sort
+
+Given an array `arr` containing `n` elements of type `T`, sort it in-place in nondecreasing order.
+
+Each partition step groups all elements equal to the pivot together and never recurses into
+them, so inputs with only `k` distinct values are sorted in `O(n log k)`.
+
+`T` must implement [Ord] and [Clone].
+
+Worst-case Time Complexity: `O(n^2)`
+Average-case Time Complexity: `O(n log n)`
+Best-case Time Complexity: `O(n)`
+Space Complexity: `O(log n)`
+Stable?: No
+*/
+#[cfg_attr(
+    any(debug_assertions, feature = "checked"),
+    contracts::ensures(is_sorted(arr)),
+    contracts::ensures(is_permutation_of(arr, &old(arr.to_vec())))
+)]
+pub fn sort<T: Ord + Clone>(arr: &mut [T]) {
+    sort_recursive(arr);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sorting::sort_test_helper::test_sort;
+
+    #[test]
+    fn test_three_way_quick_sort() {
+        test_sort(sort);
+    }
+}